@@ -1,3 +1,5 @@
+use bigint::BigInt;
+use span::Span;
 use std::fmt;
 
 #[derive(PartialEq, Clone, Debug)]
@@ -8,6 +10,7 @@ pub enum Prefix {
     Plus,
     Minus,
     Not,
+    BitNot,
 }
 
 impl fmt::Display for Prefix {
@@ -16,6 +19,7 @@ impl fmt::Display for Prefix {
             Prefix::Plus => write!(f, "+"),
             Prefix::Minus => write!(f, "-"),
             Prefix::Not => write!(f, "!"),
+            Prefix::BitNot => write!(f, "~"),
         }
     }
 }
@@ -26,12 +30,22 @@ pub enum Infix {
     Minus,
     Divide,
     Multiply,
+    Modulo,
+    Power,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     Equal,
     NotEqual,
     GreaterThanEqual,
     GreaterThan,
     LessThanEqual,
     LessThan,
+    In,
+    And,
+    Or,
 }
 
 impl fmt::Display for Infix {
@@ -41,23 +55,68 @@ impl fmt::Display for Infix {
             Infix::Minus => write!(f, "-"),
             Infix::Divide => write!(f, "/"),
             Infix::Multiply => write!(f, "*"),
+            Infix::Modulo => write!(f, "%"),
+            Infix::Power => write!(f, "**"),
+            Infix::BitAnd => write!(f, "&"),
+            Infix::BitOr => write!(f, "|"),
+            Infix::BitXor => write!(f, "^"),
+            Infix::ShiftLeft => write!(f, "<<"),
+            Infix::ShiftRight => write!(f, ">>"),
             Infix::Equal => write!(f, "=="),
             Infix::NotEqual => write!(f, "!="),
             Infix::GreaterThanEqual => write!(f, ">="),
             Infix::GreaterThan => write!(f, ">"),
             Infix::LessThanEqual => write!(f, "<="),
             Infix::LessThan => write!(f, "<"),
+            Infix::In => write!(f, "in"),
+            Infix::And => write!(f, "&&"),
+            Infix::Or => write!(f, "||"),
         }
     }
 }
 
+/// An expression node. Spans are ignored when comparing nodes, so two trees
+/// with the same shape are equal no matter where they were parsed from.
+#[derive(Clone, Debug)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+}
+
+impl PartialEq for Expr {
+    fn eq(&self, other: &Expr) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl From<ExprKind> for Expr {
+    fn from(kind: ExprKind) -> Self {
+        Expr::new(kind, Span::default())
+    }
+}
+
 #[derive(PartialEq, Clone, Debug)]
-pub enum Expr {
+pub enum ExprKind {
     Ident(Ident),
     Literal(Literal),
     Prefix(Prefix, Box<Expr>),
     Infix(Infix, Box<Expr>, Box<Expr>),
     Index(Box<Expr>, Box<Expr>),
+    /// `left[start:end]` or `left[start:end:step]`; any bound may be left out.
+    Slice {
+        left: Box<Expr>,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+        step: Option<Box<Expr>>,
+    },
+    /// `f"..."`: its text and `{...}` holes, in order.
+    Interpolated(Vec<StringPart>),
     If {
         cond: Box<Expr>,
         consequence: BlockStmt,
@@ -67,6 +126,12 @@ pub enum Expr {
         cond: Box<Expr>,
         consequence: BlockStmt,
     },
+    /// `for (item in iterable)` or `for (key, item in iterable)`.
+    For {
+        vars: Vec<Ident>,
+        iterable: Box<Expr>,
+        body: BlockStmt,
+    },
     Func {
         params: Vec<Ident>,
         body: BlockStmt,
@@ -77,21 +142,74 @@ pub enum Expr {
     },
 }
 
+#[derive(PartialEq, Clone, Debug)]
+pub enum StringPart {
+    Text(String),
+    Expr(Expr),
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub enum Literal {
     Int(i64),
+    BigInt(BigInt),
+    Float(f64),
     String(String),
+    /// A raw string keeps its `#` count so the formatter can print it back.
+    RawString(String, usize),
+    Char(char),
     Bool(bool),
+    Null,
     Array(Vec<Expr>),
     Hash(Vec<(Expr, Expr)>),
 }
 
+/// A statement node. Like `Expr`, spans do not take part in comparison.
+#[derive(Clone, Debug)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Stmt { kind, span }
+    }
+
+    /// Blank lines and comments only matter to the formatter.
+    pub fn is_trivia(&self) -> bool {
+        match self.kind {
+            StmtKind::Blank | StmtKind::Comment(_) => true,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq for Stmt {
+    fn eq(&self, other: &Stmt) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl From<StmtKind> for Stmt {
+    fn from(kind: StmtKind) -> Self {
+        Stmt::new(kind, Span::default())
+    }
+}
+
 #[derive(PartialEq, Clone, Debug)]
-pub enum Stmt {
+pub enum StmtKind {
     Blank,
+    Comment(String),
     Break,
     Continue,
     Let(Ident, Expr),
+    /// `target = value`, or `target op= value` when `op` is set. The target
+    /// is an identifier or an index expression.
+    Assign {
+        target: Expr,
+        op: Option<Infix>,
+        value: Expr,
+    },
     Return(Expr),
     Expr(Expr),
 }
@@ -103,11 +221,18 @@ pub type Program = BlockStmt;
 #[derive(PartialEq, PartialOrd, Debug, Clone)]
 pub enum Precedence {
     Lowest,
+    Or,          // ||
+    And,         // &&
     Equals,      // ==
-    LessGreater, // > or <
+    LessGreater, // > or < or in
+    BitOr,       // |
+    BitXor,      // ^
+    BitAnd,      // &
+    Shift,       // << or >>
     Sum,         // +
-    Product,     // *
+    Product,     // * or %
     Prefix,      // -X or !X
+    Power,       // **
     Call,        // myFunction(x)
     Index,       // array[index]
 }
@@ -0,0 +1,475 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+/// Limbs are stored in base 10^9 so decimal parsing and printing need no
+/// division.
+const BASE: u64 = 1_000_000_000;
+
+/// An arbitrary-precision integer, for numbers that do not fit in an `i64`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /// Least significant first, without leading zeros. Zero has no limbs.
+    limbs: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl BigInt {
+    fn new(negative: bool, mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        BigInt {
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        // Three limbs hold up to 10^27, well past `i64::MAX`.
+        if self.limbs.len() > 3 {
+            return None;
+        }
+
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0i128, |acc, &limb| acc * BASE as i128 + limb as i128);
+
+        i64::try_from(if self.negative { -magnitude } else { magnitude }).ok()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &limb| acc * BASE as f64 + limb as f64);
+
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Truncating division, like `i64`: the quotient rounds toward zero and
+    /// the remainder has the sign of `self`. `None` when `other` is zero.
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_mag(&self.limbs, &other.limbs);
+
+        Some((
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        ))
+    }
+
+    pub fn checked_div(&self, other: &BigInt) -> Option<BigInt> {
+        self.div_rem(other).map(|(quotient, _)| quotient)
+    }
+
+    pub fn checked_rem(&self, other: &BigInt) -> Option<BigInt> {
+        self.div_rem(other).map(|(_, remainder)| remainder)
+    }
+
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1);
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    /// `self * 2^n`.
+    pub fn shift_left(&self, n: u32) -> BigInt {
+        self * &BigInt::from(2).pow(n)
+    }
+
+    /// `self / 2^n`, rounded toward negative infinity like `>>` on `i64`.
+    pub fn shift_right(&self, n: u32) -> BigInt {
+        let (quotient, remainder) = match self.div_rem(&BigInt::from(2).pow(n)) {
+            Some(result) => result,
+            None => unreachable!("powers of two are never zero"),
+        };
+
+        if remainder.negative {
+            &quotient - &BigInt::from(1)
+        } else {
+            quotient
+        }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let mut magnitude = value.unsigned_abs();
+        let mut limbs = vec![];
+
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE) as u32);
+            magnitude /= BASE;
+        }
+
+        BigInt::new(value < 0, limbs)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let limbs = digits
+            .as_bytes()
+            .rchunks(9)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |acc, &b| acc * 10 + u32::from(b - b'0'))
+            })
+            .collect();
+
+        Ok(BigInt::new(negative, limbs))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        match self.limbs.split_last() {
+            None => write!(f, "0"),
+            Some((first, rest)) => {
+                write!(f, "{}", first)?;
+                for limb in rest.iter().rev() {
+                    write!(f, "{:09}", limb)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_mag(&self.limbs, &other.limbs));
+        }
+
+        match cmp_mag(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::new(other.negative, sub_mag(&other.limbs, &self.limbs)),
+            _ => BigInt::new(self.negative, sub_mag(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_mag(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0;
+
+    for i in 0..a.len().max(b.len()) {
+        let sum = carry + u64::from(*a.get(i).unwrap_or(&0)) + u64::from(*b.get(i).unwrap_or(&0));
+        result.push((sum % BASE) as u32);
+        carry = sum / BASE;
+    }
+
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    result
+}
+
+/// `a - b`, where `a >= b`.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff = i64::from(limb) - borrow - i64::from(*b.get(i).unwrap_or(&0));
+        borrow = 0;
+        if diff < 0 {
+            diff += BASE as i64;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+
+    trim(&mut result);
+    result
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u64; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let cur = result[i + j] + u64::from(x) * u64::from(y) + carry;
+            result[i + j] = cur % BASE;
+            carry = cur / BASE;
+        }
+        result[i + b.len()] = carry;
+    }
+
+    let mut result = result.into_iter().map(|limb| limb as u32).collect();
+    trim(&mut result);
+    result
+}
+
+/// Schoolbook long division, one base 10^9 digit at a time. Each digit of
+/// the quotient is found by binary search.
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = vec![];
+
+    for i in (0..a.len()).rev() {
+        remainder.insert(0, a[i]);
+        trim(&mut remainder);
+
+        let (mut lo, mut hi) = (0, (BASE - 1) as u32);
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            if cmp_mag(&mul_mag(b, &[mid]), &remainder) == Ordering::Greater {
+                hi = mid - 1;
+            } else {
+                lo = mid;
+            }
+        }
+
+        if lo > 0 {
+            remainder = sub_mag(&remainder, &mul_mag(b, &[lo]));
+        }
+        quotient[i] = lo;
+    }
+
+    trim(&mut quotient);
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use bigint::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let tests = vec![
+            ("0", "0"),
+            ("-0", "0"),
+            ("+42", "42"),
+            ("000123", "123"),
+            ("1000000000", "1000000000"),
+            (
+                "-123456789012345678901234567890",
+                "-123456789012345678901234567890",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, big(input).to_string());
+        }
+
+        for input in &["", "-", "1_000", "12a", "1.5"] {
+            assert_eq!(Err(ParseBigIntError), input.parse::<BigInt>());
+        }
+    }
+
+    #[test]
+    fn test_i64_conversion() {
+        for &value in &[0, 1, -1, 999_999_999, 1_000_000_000, i64::MAX, i64::MIN] {
+            assert_eq!(Some(value), BigInt::from(value).to_i64());
+            assert_eq!(value.to_string(), BigInt::from(value).to_string());
+        }
+
+        assert_eq!(None, big("9223372036854775808").to_i64());
+        assert_eq!(None, big("-9223372036854775809").to_i64());
+        assert_eq!(1e30, big("1000000000000000000000000000000").to_f64());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("-987654321098765432109876543210");
+
+        assert_eq!(big("-864197532086419753208641975320"), &a + &b);
+        assert_eq!(big("1111111110111111111011111111100"), &a - &b);
+        assert_eq!(
+            big("-121932631137021795226185032733622923332237463801111263526900"),
+            &a * &b
+        );
+        assert_eq!(big("0"), &a - &a);
+        assert_eq!(big("-123456789012345678901234567890"), -&a);
+        assert_eq!(
+            big("18446744073709551616"),
+            &BigInt::from(i64::MIN) * &BigInt::from(-2)
+        );
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let tests = vec![
+            ("100000000000000000000", "7", "14285714285714285714", "2"),
+            ("-100000000000000000000", "7", "-14285714285714285714", "-2"),
+            ("100000000000000000000", "-7", "-14285714285714285714", "2"),
+            (
+                "121932631137021795226185032733622923332237463801111263526900",
+                "123456789012345678901234567890",
+                "987654321098765432109876543210",
+                "0",
+            ),
+            ("5", "123456789012345678901234567890", "0", "5"),
+        ];
+
+        for (a, b, quotient, remainder) in tests {
+            assert_eq!(
+                Some((big(quotient), big(remainder))),
+                big(a).div_rem(&big(b))
+            );
+        }
+
+        assert_eq!(None, big("1").div_rem(&big("0")));
+    }
+
+    #[test]
+    fn test_pow_and_shift() {
+        assert_eq!(big("1"), big("12345").pow(0));
+        assert_eq!(big("1267650600228229401496703205376"), big("2").pow(100));
+        assert_eq!(big("-27"), big("-3").pow(3));
+        assert_eq!(
+            big("1267650600228229401496703205376"),
+            big("1").shift_left(100)
+        );
+        assert_eq!(
+            big("1"),
+            big("1267650600228229401496703205376").shift_right(100)
+        );
+        assert_eq!(big("-3"), big("-5").shift_right(1));
+        assert_eq!(big("2"), big("5").shift_right(1));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut values = vec![
+            big("10"),
+            big("-1000000000000"),
+            big("0"),
+            big("-3"),
+            big("1000000000000"),
+        ];
+        values.sort();
+
+        assert_eq!(
+            vec![
+                big("-1000000000000"),
+                big("-3"),
+                big("0"),
+                big("10"),
+                big("1000000000000")
+            ],
+            values
+        );
+    }
+}
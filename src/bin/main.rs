@@ -2,29 +2,42 @@ extern crate pua_lang;
 extern crate rustyline;
 extern crate rustyline_derive;
 
+use pua_lang::diagnostics::Diagnostic;
 use pua_lang::evaluator::builtins::new_builtins;
 use pua_lang::evaluator::env::Env;
-use pua_lang::evaluator::Evaluator;
-use pua_lang::lexer::{is_whitespace, Lexer};
+use pua_lang::evaluator::object::Object;
+use pua_lang::evaluator::{Evaluator, OutOfRange};
+use pua_lang::formatter::Formatter;
+use pua_lang::lexer::{LexError, Lexer};
 use pua_lang::parser::{ParseError, Parser};
-use pua_lang::token::Token;
+use pua_lang::span::Span;
+use pua_lang::token::{SpannedToken, Token};
 use std::borrow::Cow::{self, Borrowed, Owned};
 use std::cell::RefCell;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::process;
 use std::rc::Rc;
+use std::time::Instant;
 
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
-use rustyline::highlight::{Highlighter, MatchingBracketHighlighter};
+use rustyline::highlight::Highlighter;
 use rustyline::hint::{Hinter, HistoryHinter};
 use rustyline::validate::{self, Validator};
 use rustyline::KeyEvent;
 use rustyline::{Cmd, CompletionType, Config, Context, EditMode, Editor};
 use rustyline_derive::Helper;
 
+/// Name shown for REPL input in diagnostics.
+const FILE_NAME: &str = "<repl>";
+
 #[derive(Helper)]
 struct PuaHelper {
     env: Rc<RefCell<Env>>,
-    highlighter: MatchingBracketHighlighter,
+    builtins: HashSet<String>,
     hinter: HistoryHinter,
     colored_prompt: String,
 }
@@ -38,21 +51,84 @@ impl Completer for PuaHelper {
         pos: usize,
         _ctx: &Context,
     ) -> Result<(usize, Vec<Pair>), ReadlineError> {
-        let (start, word) = extract_word(line, pos);
-        let mut matches: Vec<Pair> = Vec::new();
-        for key in self.env.borrow().store.keys() {
-            if key.starts_with(word) {
-                matches.push(Pair {
-                    display: key.to_string(),
-                    replacement: key.to_string(),
-                });
+        let line = &line[..pos];
+        let tokens = lex_line(line)
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+
+        // The word being typed, if the cursor is right after one.
+        let (start, word) = match tokens.last() {
+            Some(tok) if tok.span.end.offset == pos && is_word(&line[tok.span.start.offset..]) => {
+                (tok.span.start.offset, &line[tok.span.start.offset..])
             }
-        }
+            _ => (pos, ""),
+        };
+        let before = match word {
+            "" => &tokens[..],
+            _ => &tokens[..tokens.len() - 1],
+        };
+
+        let mut candidates = match before {
+            [.., SpannedToken {
+                token: Token::Ident(obj),
+                ..
+            }, SpannedToken {
+                token: Token::Dot, ..
+            }] => self.hash_keys(obj),
+            _ if word.is_empty() => vec![],
+            _ => {
+                let mut names = self.env.borrow().names();
+                names.extend(Lexer::KEYWORDS.iter().map(|k| k.to_string()));
+                names
+            }
+        };
+        candidates.retain(|c| c.starts_with(word));
+        candidates.sort();
+        candidates.dedup();
+
+        let matches = candidates
+            .into_iter()
+            .map(|c| Pair {
+                display: c.clone(),
+                replacement: c,
+            })
+            .collect();
 
         Ok((start, matches))
     }
 }
 
+impl PuaHelper {
+    /// Keys of the hash bound to `name` that can follow a `.`.
+    fn hash_keys(&self, name: &str) -> Vec<String> {
+        match self.env.borrow_mut().get(name.to_string()) {
+            Some(Object::Hash(hash)) => hash
+                .keys()
+                .filter_map(|key| match key {
+                    Object::String(key) if is_word(key) => Some(key.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// True if `text` reads as an identifier or keyword.
+fn is_word(text: &str) -> bool {
+    match Lexer::new(text).next_token() {
+        Ok(tok) => {
+            tok.span.end.offset == text.len()
+                && match tok.token {
+                    Token::Ident(_) => true,
+                    _ => Lexer::KEYWORDS.contains(&text),
+                }
+        }
+        Err(_) => false,
+    }
+}
+
 impl Hinter for PuaHelper {
     type Hint = String;
 
@@ -79,11 +155,12 @@ impl Highlighter for PuaHelper {
     }
 
     fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
-        self.highlighter.highlight(line, pos)
+        Owned(highlight_line(line, pos, &self.builtins))
     }
 
-    fn highlight_char(&self, line: &str, pos: usize) -> bool {
-        self.highlighter.highlight_char(line, pos)
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        // Any edit can change how the rest of the line lexes.
+        true
     }
 }
 
@@ -92,6 +169,11 @@ impl Validator for PuaHelper {
         &self,
         ctx: &mut validate::ValidationContext,
     ) -> rustyline::Result<validate::ValidationResult> {
+        // `:commands` are handled by the REPL itself.
+        if ctx.input().trim_start().starts_with(':') {
+            return Ok(validate::ValidationResult::Valid(None));
+        }
+
         let mut parser = Parser::new(Lexer::new(ctx.input()));
         let _ = parser.parse();
         let errors = parser.get_errors();
@@ -100,10 +182,16 @@ impl Validator for PuaHelper {
             0 => validate::ValidationResult::Valid(None),
             _ => match &errors[0] {
                 ParseError::UnexpectedToken {
-                    want: _,
-                    got: Token::Eof,
-                } => validate::ValidationResult::Incomplete,
-                x => validate::ValidationResult::Invalid(Some(format!("{}", x))),
+                    got: Token::Eof, ..
+                }
+                | ParseError::Lex(LexError::UnterminatedString { .. })
+                | ParseError::Lex(LexError::UnterminatedComment { .. }) => {
+                    validate::ValidationResult::Incomplete
+                }
+                x => validate::ValidationResult::Invalid(Some(format!(
+                    "\n{}",
+                    Diagnostic::from(x).render(FILE_NAME, ctx.input())
+                ))),
             },
         })
     }
@@ -113,35 +201,443 @@ impl Validator for PuaHelper {
     }
 }
 
-// ---- Completer ----
+// ---- Highlighter ----
+
+const STYLE_KEYWORD: &str = "\x1b[1;35m";
+const STYLE_LITERAL: &str = "\x1b[33m";
+const STYLE_STRING: &str = "\x1b[32m";
+const STYLE_BUILTIN: &str = "\x1b[36m";
+const STYLE_COMMENT: &str = "\x1b[90m";
+const STYLE_ERROR: &str = "\x1b[4;31m";
+const STYLE_BRACKET: &str = "\x1b[1;34m";
+const STYLE_RESET: &str = "\x1b[0m";
 
-/// Given a `line` and a cursor `pos`ition,
-/// try to find backward the start of a word.
-/// Return (0, `line[..pos]`) if no break char has been found.
-/// Return the word and its start position (idx, `line[idx..pos]`) otherwise.
-pub fn extract_word<'l>(line: &'l str, pos: usize) -> (usize, &'l str) {
-    let line = &line[..pos];
-    if line.is_empty() {
-        return (0, line);
+/// Lexes as much of `line` as possible, keeping lex errors in place of the
+/// tokens they stand for.
+fn lex_line(line: &str) -> Vec<Result<SpannedToken, LexError>> {
+    let mut lexer = Lexer::new(line);
+    let mut tokens = vec![];
+    let mut end = 0;
+
+    loop {
+        let next = lexer.next_token();
+        if let Ok(SpannedToken {
+            token: Token::Eof, ..
+        }) = next
+        {
+            break;
+        }
+        let span = token_span(&next);
+        // An error that does not move the lexer on would repeat forever.
+        if span.end.offset <= end && !tokens.is_empty() {
+            break;
+        }
+        end = span.end.offset;
+        tokens.push(next);
     }
-    let mut start = None;
-    for (i, c) in line.char_indices().rev() {
-        if is_whitespace(c) {
-            start = Some(i + c.len_utf8());
+
+    tokens
+}
+
+fn token_span(token: &Result<SpannedToken, LexError>) -> Span {
+    match token {
+        Ok(tok) => tok.span,
+        Err(err) => err.span(),
+    }
+}
+
+fn token_style(token: &Token, text: &str, builtins: &HashSet<String>) -> Option<&'static str> {
+    match token {
+        Token::Func
+        | Token::Let
+        | Token::If
+        | Token::Else
+        | Token::While
+        | Token::Break
+        | Token::Continue
+        | Token::Return => Some(STYLE_KEYWORD),
+        Token::Int(_) | Token::BigInt(_) | Token::Float(_) | Token::Bool(_) | Token::Null => {
+            Some(STYLE_LITERAL)
+        }
+        Token::String(_)
+        | Token::RawString(..)
+        | Token::Char(_)
+        | Token::InterpString(_)
+        | Token::InterpStart(_)
+        | Token::InterpMid(_)
+        | Token::InterpEnd(_) => Some(STYLE_STRING),
+        Token::Comment(_) => Some(STYLE_COMMENT),
+        Token::Illegal => Some(STYLE_ERROR),
+        Token::Ident(name) if builtins.contains(name) => Some(STYLE_BUILTIN),
+        Token::Ident(_) => None,
+        // Operators spelled as words, such as `联动`, read as keywords.
+        _ if text.starts_with(char::is_alphabetic) => Some(STYLE_KEYWORD),
+        _ => None,
+    }
+}
+
+/// Indices of the bracket at the cursor and of the one matching it.
+fn matching_brackets(
+    tokens: &[Result<SpannedToken, LexError>],
+    pos: usize,
+) -> Option<(usize, usize)> {
+    let direction = |i: usize| match &tokens[i] {
+        Ok(tok) => match tok.token {
+            Token::Lparen | Token::Lbracket | Token::Lbrace => 1,
+            Token::Rparen | Token::Rbracket | Token::Rbrace => -1,
+            _ => 0,
+        },
+        Err(_) => 0,
+    };
+    let at = (0..tokens.len())
+        .filter(|&i| direction(i) != 0)
+        .find(|&i| token_span(&tokens[i]).end.offset == pos)
+        .or_else(|| {
+            (0..tokens.len())
+                .filter(|&i| direction(i) != 0)
+                .find(|&i| token_span(&tokens[i]).start.offset == pos)
+        })?;
+
+    let mut depth = 0;
+    let mut i = at;
+    loop {
+        depth += direction(i);
+        if depth == 0 {
+            return Some((at, i));
         }
+        i = match direction(at) {
+            1 if i + 1 < tokens.len() => i + 1,
+            -1 if i > 0 => i - 1,
+            _ => return None,
+        };
     }
+}
+
+fn highlight_line(line: &str, pos: usize, builtins: &HashSet<String>) -> String {
+    let tokens = lex_line(line);
+    let brackets = matching_brackets(&tokens, pos);
+    let mut out = String::with_capacity(line.len() * 2);
+    let mut last = 0;
 
-    match start {
-        Some(start) => (start, &line[start..]),
-        None => (0, line),
+    for (i, token) in tokens.iter().enumerate() {
+        let span = token_span(token);
+        let start = span.start.offset.max(last);
+        let end = span.end.offset.max(start);
+        let text = &line[start..end];
+
+        let style = match token {
+            _ if brackets.is_some_and(|(a, b)| i == a || i == b) => Some(STYLE_BRACKET),
+            Ok(tok) => token_style(&tok.token, text, builtins),
+            Err(_) => Some(STYLE_ERROR),
+        };
+
+        out.push_str(&line[last..start]);
+        match style {
+            Some(style) => {
+                out.push_str(style);
+                out.push_str(text);
+                out.push_str(STYLE_RESET);
+            }
+            None => out.push_str(text),
+        }
+        last = end;
     }
+    out.push_str(&line[last..]);
+
+    out
 }
 
 // ---- Main ----
+
+const USAGE: &str = "Usage: pua-lang [options] [file | -]
+
+Runs the PUA program in `file`, or `-` for stdin. Without a file, reads the
+program from stdin when it is piped, and starts the REPL otherwise.
+
+Options:
+  -e <code>   run `code` instead of a file
+  -i          start the REPL after running the program, with its bindings
+  --strict-index
+              make indexing past the end of an array or string an error
+              instead of null
+  -h, --help  print this message";
+
+/// Where the program to run comes from.
+enum Source {
+    File(String),
+    Inline(String),
+    Stdin,
+}
+
+struct Options {
+    source: Option<Source>,
+    interactive: bool,
+    strict_index: bool,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut options = Options {
+        source: None,
+        interactive: false,
+        strict_index: false,
+    };
+
+    while let Some(arg) = args.next() {
+        let source = match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-i" => {
+                options.interactive = true;
+                continue;
+            }
+            "--strict-index" => {
+                options.strict_index = true;
+                continue;
+            }
+            "-e" => match args.next() {
+                Some(code) => Source::Inline(code),
+                None => return Err(String::from("`-e` needs an argument")),
+            },
+            "-" => Source::Stdin,
+            flag if flag.starts_with('-') => return Err(format!("unknown option `{}`", flag)),
+            _ => Source::File(arg),
+        };
+        if options.source.is_some() {
+            return Err(String::from("only one program can be run at a time"));
+        }
+        options.source = Some(source);
+    }
+
+    Ok(options)
+}
+
+/// Runs a whole program, reporting any error on stderr. Returns the value
+/// of the last statement, or `Err` once the errors have been reported;
+/// `quit`/`淘汰` exits the process with its own code.
+fn run(evaluator: &mut Evaluator, file_name: &str, source: &str) -> Result<Option<Object>, ()> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse();
+    let errors = parser.get_errors();
+
+    if !errors.is_empty() {
+        for err in &errors {
+            report(Diagnostic::from(err), file_name, source);
+        }
+        return Err(());
+    }
+
+    // `_` follows every top-level value, so a session saved from the REPL
+    // replays the same way when it is run or loaded.
+    let bind_last = |evaluator: &mut Evaluator, value: &Object| {
+        evaluator.env.borrow_mut().set(String::from("_"), value);
+    };
+
+    match evaluator.eval_with(&program, bind_last) {
+        Some(Object::Error(msg)) => {
+            let span = evaluator.error_span().unwrap_or_default();
+            report(Diagnostic::runtime(&msg, span), file_name, source);
+            Err(())
+        }
+        evaluated => Ok(evaluated),
+    }
+}
+
+/// Prints `diagnostic` to stderr, with a red `error` on terminals.
+fn report(diagnostic: Diagnostic, file_name: &str, source: &str) {
+    let rendered = diagnostic.render(file_name, source);
+
+    if io::stderr().is_terminal() {
+        eprint!(
+            "\x1b[1;31merror\x1b[0m\x1b[1m{}\x1b[0m",
+            rendered.trim_start_matches("error")
+        );
+    } else {
+        eprint!("{}", rendered);
+    }
+}
+
 fn main() {
+    let options = parse_args(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("error: {}\n\n{}", err, USAGE);
+        process::exit(2);
+    });
+
     let env = Env::from(new_builtins());
     let mut evaluator = Evaluator::new(Rc::new(RefCell::new(env)));
+    if options.strict_index {
+        evaluator.set_out_of_range(OutOfRange::Error);
+    }
+
+    let source = match options.source {
+        Some(source) => Some(source),
+        None if !io::stdin().is_terminal() => Some(Source::Stdin),
+        None => None,
+    };
+
+    if let Some(source) = source {
+        let (file_name, code) = match source {
+            Source::File(path) => match fs::read_to_string(&path) {
+                Ok(code) => (path, code),
+                Err(err) => {
+                    eprintln!("error: cannot read `{}`: {}", path, err);
+                    process::exit(1);
+                }
+            },
+            Source::Inline(code) => (String::from("<-e>"), code),
+            Source::Stdin => {
+                let mut code = String::new();
+                if let Err(err) = io::stdin().read_to_string(&mut code) {
+                    eprintln!("error: cannot read stdin: {}", err);
+                    process::exit(1);
+                }
+                (String::from("<stdin>"), code)
+            }
+        };
 
+        let ok = run(&mut evaluator, &file_name, &code).is_ok();
+        if !options.interactive {
+            process::exit(if ok { 0 } else { 1 });
+        }
+    }
+
+    repl(evaluator);
+}
+
+// ---- REPL ----
+
+const HELP: &str = ":help           show this message
+:env            list the bindings in scope
+:reset          forget all bindings
+:load <file>    run a file in this session
+:save <file>    write the inputs of this session to a file
+:ast <code>     print the syntax tree of `code`
+:tokens <code>  print the tokens of `code`
+:fmt            reformat the last input
+:time <code>    evaluate `code` and print how long it took";
+
+/// What the REPL keeps between two inputs.
+struct Session {
+    evaluator: Evaluator,
+    /// Inputs that were evaluated without errors, for `:save`.
+    inputs: Vec<String>,
+    last_input: Option<String>,
+}
+
+impl Session {
+    /// Evaluates one input and prints its value, which `run` has bound to
+    /// `_`. The session carries on after errors.
+    fn eval(&mut self, line: &str) {
+        self.last_input = Some(line.to_string());
+
+        match run(&mut self.evaluator, FILE_NAME, line) {
+            Ok(Some(evaluated)) => println!("{}\n", evaluated),
+            Ok(None) => {}
+            Err(()) => return,
+        }
+        self.inputs.push(line.trim_end().to_string());
+    }
+
+    /// Runs a `:command`, given without its colon.
+    fn command(&mut self, input: &str) {
+        let input = input.trim();
+        let (name, arg) = match input.find(char::is_whitespace) {
+            Some(i) => (&input[..i], input[i..].trim()),
+            None => (input, ""),
+        };
+
+        match name {
+            "help" => println!("{}", HELP),
+            "env" => self.print_env(),
+            "reset" => {
+                *self.evaluator.env.borrow_mut() = Env::from(new_builtins());
+                self.inputs.clear();
+                self.last_input = None;
+            }
+            "load" => match fs::read_to_string(arg) {
+                Ok(code) => {
+                    if run(&mut self.evaluator, arg, &code).is_ok() {
+                        self.inputs.push(code.trim_end().to_string());
+                    }
+                }
+                Err(err) => println!("cannot read `{}`: {}", arg, err),
+            },
+            "save" => {
+                let mut code = self.inputs.join("\n");
+                code.push('\n');
+                if let Err(err) = fs::write(arg, code) {
+                    println!("cannot write `{}`: {}", arg, err);
+                }
+            }
+            "ast" => {
+                let mut parser = Parser::new(Lexer::new(arg));
+                let program = parser.parse();
+                print_parse_errors(&parser.get_errors(), arg);
+                println!("{:#?}", program);
+            }
+            "tokens" => print_tokens(arg),
+            "fmt" => match &self.last_input {
+                Some(input) => {
+                    let mut parser = Parser::new(Lexer::new(input));
+                    let program = parser.parse();
+                    let errors = parser.get_errors();
+                    if errors.is_empty() {
+                        println!("{}", Formatter::new().format(program));
+                    } else {
+                        print_parse_errors(&errors, input);
+                    }
+                }
+                None => println!("nothing to format yet"),
+            },
+            "time" => {
+                let start = Instant::now();
+                self.eval(arg);
+                println!("took {:?}", start.elapsed());
+            }
+            _ => println!("unknown command `:{}`, try `:help`", name),
+        }
+    }
+
+    /// Prints the bindings made in this session, leaving out builtins.
+    fn print_env(&self) {
+        let env = self.evaluator.env.borrow();
+        let mut bindings = env
+            .store
+            .iter()
+            .filter(|(_, value)| !matches!(value, Object::Builtin(..)))
+            .collect::<Vec<_>>();
+        bindings.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (name, value) in bindings {
+            println!("{} = {}", name, value);
+        }
+    }
+}
+
+fn print_parse_errors(errors: &[ParseError], source: &str) {
+    for err in errors {
+        report(Diagnostic::from(err), FILE_NAME, source);
+    }
+}
+
+fn print_tokens(source: &str) {
+    let mut lexer = Lexer::new(source);
+    loop {
+        match lexer.next_token() {
+            Ok(SpannedToken {
+                token: Token::Eof, ..
+            }) => break,
+            Ok(tok) => println!("{:>7}  {:?}", tok.span.to_string(), tok.token),
+            Err(err) => {
+                report(Diagnostic::from(&err), FILE_NAME, source);
+                break;
+            }
+        }
+    }
+}
+
+fn repl(evaluator: Evaluator) {
     let config = Config::builder()
         .history_ignore_space(true)
         .completion_type(CompletionType::List)
@@ -149,7 +645,7 @@ fn main() {
         .build();
     let h = PuaHelper {
         env: evaluator.env.clone(),
-        highlighter: MatchingBracketHighlighter::new(),
+        builtins: new_builtins().keys().cloned().collect(),
         hinter: HistoryHinter {},
         colored_prompt: "\x1b[32m>>\x1b[0m ".to_owned(),
     };
@@ -162,21 +658,22 @@ fn main() {
     }
 
     println!("Hello! This is the PUA programming language!");
-    println!("Feel free to type in commands\n");
+    println!("Feel free to type in commands, or `:help` for more\n");
+
+    let mut session = Session {
+        evaluator,
+        inputs: vec![],
+        last_input: None,
+    };
 
     loop {
         match rl.readline(">> ") {
             Ok(line) => {
                 rl.add_history_entry(&line);
 
-                let mut parser = Parser::new(Lexer::new(&line));
-                let program = parser.parse();
-
-                // No error check: rl should handle that.
-                // Yes this is reckless.
-
-                if let Some(evaluated) = evaluator.eval(&program) {
-                    println!("{}\n", evaluated);
+                match line.trim_start().strip_prefix(':') {
+                    Some(command) => session.command(command),
+                    None => session.eval(&line),
                 }
             }
             Err(ReadlineError::Interrupted) => {
@@ -184,13 +681,15 @@ fn main() {
                 break;
             }
             Err(ReadlineError::Eof) => {
-                println!("");
+                println!();
                 break;
             }
             Err(err) => {
                 println!("Error: {:?}", err);
             }
         }
-        rl.append_history("pua_history.txt");
+        if let Err(err) = rl.append_history("pua_history.txt") {
+            println!("Error: {:?}", err);
+        }
     }
 }
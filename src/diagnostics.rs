@@ -0,0 +1,324 @@
+//! Renders lexer, parser and runtime errors rustc-style:
+//!
+//! ```text
+//! error: expected `)`, found `;`
+//!  --> fib.pua:3:15
+//!   |
+//! 3 | let x = (1 + 2;
+//!   |               ^
+//!   = hint: insert `)` here
+//! ```
+extern crate unicode_width;
+
+use self::unicode_width::UnicodeWidthChar;
+use lexer::unescape::EscapeError;
+use lexer::LexError;
+use parser::ParseError;
+use span::{Pos, Span};
+use std::fmt::Write;
+use token::Token;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, span: Span) -> Self {
+        Diagnostic {
+            message,
+            span,
+            hint: None,
+        }
+    }
+
+    pub fn with_hint(mut self, hint: String) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    /// A diagnostic for an `Object::Error` raised while evaluating the
+    /// expression at `span`.
+    pub fn runtime(message: &str, span: Span) -> Self {
+        let diagnostic = Diagnostic::new(message.to_string(), span);
+
+        match message.strip_prefix("identifier not found: ") {
+            Some(name) => diagnostic.with_hint(format!(
+                "bind it first, e.g. `let {} = ...;` (`赋能 {} = ...;`)",
+                name, name
+            )),
+            None => diagnostic,
+        }
+    }
+
+    /// Renders the diagnostic against `source`, the text the span refers to.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let span = self.shown_span(source);
+        let start = span.start;
+        let line_no = start.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        let mut out = String::new();
+
+        let _ = writeln!(out, "error: {}", self.message);
+        let _ = writeln!(
+            out,
+            "{}--> {}:{}:{}",
+            gutter, file_name, start.line, start.column
+        );
+
+        if let Some(line) = source.lines().nth(start.line - 1) {
+            let line = line.trim_end_matches('\r');
+            let _ = writeln!(out, "{} |", gutter);
+            let _ = writeln!(out, "{} | {}", line_no, line);
+            let _ = writeln!(out, "{} | {}", gutter, Self::underline(span, line));
+        }
+
+        if let Some(hint) = &self.hint {
+            let _ = writeln!(out, "{} = hint: {}", gutter, hint);
+        }
+
+        out
+    }
+
+    /// The span to point at in `source`. An error at the end of input after
+    /// a trailing newline is on a line of its own that `lines` does not
+    /// yield, so it is shown just after the last character of the last line.
+    fn shown_span(&self, source: &str) -> Span {
+        if !source.ends_with('\n') || self.span.start.line <= source.lines().count() {
+            return self.span;
+        }
+
+        let body = &source[..source.len() - 1];
+        let line_start = body.rfind('\n').map_or(0, |i| i + 1);
+        let last = body[line_start..].trim_end_matches('\r');
+        let end = Pos::new(
+            line_start + last.len(),
+            self.span.start.line - 1,
+            last.chars().count() + 1,
+        );
+
+        Span::new(end, end)
+    }
+
+    /// Carets under the spanned part of `line`, measured in terminal
+    /// columns so that wide CJK characters line up.
+    fn underline(span: Span, line: &str) -> String {
+        let start = span.start;
+        let end = span.end;
+        let first = start.column - 1;
+        let last = if end.line == start.line {
+            end.column - 1
+        } else {
+            line.chars().count()
+        };
+
+        let width = |c: char| c.width().unwrap_or(0);
+        let padding = line.chars().take(first).map(width).sum::<usize>();
+        let carets = line
+            .chars()
+            .skip(first)
+            .take(last.saturating_sub(first))
+            .map(width)
+            .sum::<usize>();
+
+        format!("{}{}", " ".repeat(padding), "^".repeat(carets.max(1)))
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Eof => String::from("end of input"),
+        Token::Blank => String::from("a blank line"),
+        t => format!("`{}`", t),
+    }
+}
+
+impl<'a> From<&'a ParseError> for Diagnostic {
+    fn from(err: &'a ParseError) -> Self {
+        match err {
+            ParseError::UnexpectedToken {
+                want: Some(Token::Ident(_)),
+                got,
+                span,
+            } => Diagnostic::new(
+                format!("expected an identifier, found {}", describe(got)),
+                *span,
+            )
+            .with_hint(String::from("names start with a letter or `_`")),
+            ParseError::UnexpectedToken {
+                want: Some(want),
+                got,
+                span,
+            } => {
+                let diagnostic = Diagnostic::new(
+                    format!("expected `{}`, found {}", want, describe(got)),
+                    *span,
+                );
+                match got {
+                    Token::Eof => diagnostic
+                        .with_hint(format!("the input ended early; is a `{}` missing?", want)),
+                    _ => diagnostic.with_hint(format!("insert `{}` here", want)),
+                }
+            }
+            ParseError::UnexpectedToken {
+                want: None,
+                got,
+                span,
+            } => Diagnostic::new(
+                format!("expected an expression, found {}", describe(got)),
+                *span,
+            )
+            .with_hint(format!("{} cannot start an expression", describe(got))),
+            ParseError::InvalidAssignment { span } => {
+                Diagnostic::new(String::from("invalid assignment target"), *span).with_hint(
+                    String::from("only names, `a[i]` and `a.key` can be assigned to"),
+                )
+            }
+            ParseError::Lex(err) => Diagnostic::from(err),
+        }
+    }
+}
+
+impl<'a> From<&'a LexError> for Diagnostic {
+    fn from(err: &'a LexError) -> Self {
+        match err {
+            LexError::UnterminatedString { span } => {
+                Diagnostic::new(String::from("unterminated string literal"), *span)
+                    .with_hint(String::from("add a closing `\"`"))
+            }
+            LexError::UnterminatedChar { span } => {
+                Diagnostic::new(String::from("unterminated character literal"), *span)
+                    .with_hint(String::from("add a closing `'`"))
+            }
+            LexError::UnterminatedComment { span } => {
+                Diagnostic::new(String::from("unterminated block comment"), *span)
+                    .with_hint(String::from("add a closing `*/`"))
+            }
+            LexError::InvalidEscape {
+                kind: EscapeError::ZeroChars,
+                span,
+            } => Diagnostic::new(String::from("empty character literal"), *span)
+                .with_hint(String::from("use `\"\"` for an empty string")),
+            LexError::InvalidEscape {
+                kind: EscapeError::MoreThanOneChar,
+                span,
+            } => Diagnostic::new(
+                String::from("character literal may only contain one character"),
+                *span,
+            )
+            .with_hint(String::from("use double quotes for a string")),
+            LexError::InvalidEscape { kind, span } => Diagnostic::new(
+                format!(
+                    "invalid escape in string literal: {}",
+                    describe_escape(kind)
+                ),
+                *span,
+            )
+            .with_hint(String::from(
+                r#"valid escapes are \n \r \t \\ \0 \' \" \x7F and \u{7FFF}"#,
+            )),
+            LexError::IllegalCharacter { ch, span } => {
+                Diagnostic::new(format!("illegal character {:?}", ch), *span)
+                    .with_hint(String::from("this character is not part of pua-lang"))
+            }
+        }
+    }
+}
+
+fn describe_escape(kind: &EscapeError) -> &'static str {
+    match kind {
+        EscapeError::LoneSlash => "`\\` at the end of the string",
+        EscapeError::InvalidEscape => "unknown character escape",
+        EscapeError::BareCarriageReturn | EscapeError::BareCarriageReturnInRawString => {
+            "bare carriage return"
+        }
+        EscapeError::TooShortHexEscape => "numeric escape is too short",
+        EscapeError::InvalidCharInHexEscape => "invalid character in numeric escape",
+        EscapeError::OutOfRangeHexEscape => "numeric escape is out of range",
+        EscapeError::NoBraceInUnicodeEscape => "`\\u` must be followed by `{`",
+        EscapeError::InvalidCharInUnicodeEscape => "invalid character in unicode escape",
+        EscapeError::EmptyUnicodeEscape => "empty unicode escape",
+        EscapeError::UnclosedUnicodeEscape => "unterminated unicode escape",
+        EscapeError::LeadingUnderscoreUnicodeEscape => "unicode escape starts with `_`",
+        EscapeError::OverlongUnicodeEscape => "unicode escape has more than 6 digits",
+        EscapeError::LoneSurrogateUnicodeEscape => "unicode escape is a lone surrogate",
+        EscapeError::OutOfRangeUnicodeEscape => "unicode escape is out of range",
+        _ => "malformed escape",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diagnostics::Diagnostic;
+    use lexer::Lexer;
+    use parser::Parser;
+    use span::{Pos, Span};
+
+    fn render(input: &str) -> String {
+        let mut parser = Parser::new(Lexer::new(input));
+        parser.parse();
+
+        Diagnostic::from(&parser.get_errors()[0]).render("test.pua", input)
+    }
+
+    #[test]
+    fn test_render_parse_error() {
+        assert_eq!(
+            r#"error: expected `)`, found `;`
+ --> test.pua:2:14
+  |
+2 | 赋能 x = (1 + 2;
+  |                ^
+  = hint: insert `)` here
+"#,
+            render("1;\n赋能 x = (1 + 2;")
+        );
+    }
+
+    #[test]
+    fn test_render_lex_error() {
+        assert_eq!(
+            r#"error: invalid escape in string literal: unknown character escape
+ --> test.pua:1:11
+  |
+1 | let s = "a\qb";
+  |           ^^
+  = hint: valid escapes are \n \r \t \\ \0 \' \" \x7F and \u{7FFF}
+"#,
+            render("let s = \"a\\qb\";")
+        );
+    }
+
+    #[test]
+    fn test_render_runtime_error() {
+        let span = Span::new(Pos::new(13, 2, 5), Pos::new(16, 2, 8));
+
+        assert_eq!(
+            r#"error: identifier not found: foo
+ --> <repl>:2:5
+  |
+2 | 1 + foo
+  |     ^^^
+  = hint: bind it first, e.g. `let foo = ...;` (`赋能 foo = ...;`)
+"#,
+            Diagnostic::runtime("identifier not found: foo", span)
+                .render("<repl>", "let a = 1;\n1 + foo")
+        );
+    }
+
+    #[test]
+    fn test_render_error_at_end_of_input() {
+        assert_eq!(
+            r#"error: expected an expression, found end of input
+ --> test.pua:2:10
+  |
+2 | let y = (
+  |          ^
+  = hint: end of input cannot start an expression
+"#,
+            render("1;\nlet y = (\n")
+        );
+    }
+}
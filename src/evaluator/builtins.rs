@@ -1,10 +1,16 @@
+extern crate unicode_segmentation;
+
+use self::unicode_segmentation::UnicodeSegmentation;
+use bigint::BigInt;
 use evaluator::object::*;
+use evaluator::Evaluator;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 pub fn new_builtins() -> HashMap<String, Object> {
     let mut builtins = HashMap::new();
     // Monkey builtins
-    builtins.insert(String::from("len"), Object::Builtin(1, monkey_len));
+    builtins.insert(String::from("len"), Object::Builtin(-1, monkey_len));
     builtins.insert(String::from("first"), Object::Builtin(1, monkey_first));
     builtins.insert(String::from("last"), Object::Builtin(1, monkey_last));
     builtins.insert(String::from("rest"), Object::Builtin(1, monkey_rest));
@@ -17,6 +23,36 @@ pub fn new_builtins() -> HashMap<String, Object> {
     builtins.insert(String::from("repr"), Object::Builtin(1, pua_repr));
     builtins.insert(String::from("str"), Object::Builtin(1, pua_str));
     builtins.insert(String::from("atoi"), Object::Builtin(1, pua_atoi));
+    builtins.insert(String::from("range"), Object::Builtin(-1, pua_range));
+    builtins.insert(String::from("int"), Object::Builtin(1, pua_int));
+    builtins.insert(String::from("float"), Object::Builtin(1, pua_float));
+    builtins.insert(String::from("split"), Object::Builtin(-1, pua_split));
+    builtins.insert(String::from("join"), Object::Builtin(-1, pua_join));
+    builtins.insert(String::from("trim"), Object::Builtin(1, pua_trim));
+    builtins.insert(String::from("upper"), Object::Builtin(1, pua_upper));
+    builtins.insert(String::from("lower"), Object::Builtin(1, pua_lower));
+    builtins.insert(String::from("replace"), Object::Builtin(3, pua_replace));
+    builtins.insert(String::from("contains"), Object::Builtin(2, pua_contains));
+    builtins.insert(
+        String::from("starts_with"),
+        Object::Builtin(2, pua_starts_with),
+    );
+    builtins.insert(String::from("ends_with"), Object::Builtin(2, pua_ends_with));
+    builtins.insert(String::from("find"), Object::Builtin(2, pua_find));
+    builtins.insert(String::from("chars"), Object::Builtin(1, pua_chars));
+    builtins.insert(String::from("repeat"), Object::Builtin(2, pua_repeat));
+    builtins.insert(String::from("map"), Object::Builtin(2, pua_map));
+    builtins.insert(String::from("filter"), Object::Builtin(2, pua_filter));
+    builtins.insert(String::from("reduce"), Object::Builtin(-1, pua_reduce));
+    builtins.insert(String::from("sort"), Object::Builtin(-1, pua_sort));
+    builtins.insert(String::from("sort_by"), Object::Builtin(2, pua_sort_by));
+    builtins.insert(String::from("any"), Object::Builtin(-1, pua_any));
+    builtins.insert(String::from("all"), Object::Builtin(-1, pua_all));
+    builtins.insert(String::from("zip"), Object::Builtin(-1, pua_zip));
+    builtins.insert(String::from("enumerate"), Object::Builtin(1, pua_enumerate));
+    builtins.insert(String::from("reverse"), Object::Builtin(1, pua_reverse));
+    builtins.insert(String::from("flatten"), Object::Builtin(-1, pua_flatten));
+    builtins.insert(String::from("unique"), Object::Builtin(1, pua_unique));
 
     // Aba-aba builtins
     builtins.insert(String::from("淘汰"), Object::Builtin(-1, pua_quit));
@@ -25,18 +61,71 @@ pub fn new_builtins() -> HashMap<String, Object> {
     builtins.insert(String::from("复用"), Object::Builtin(1, pua_repr));
     builtins.insert(String::from("疏通"), Object::Builtin(1, pua_str));
     builtins.insert(String::from("量化"), Object::Builtin(1, pua_atoi));
+    builtins.insert(String::from("排期"), Object::Builtin(-1, pua_range));
+    builtins.insert(String::from("收口"), Object::Builtin(1, pua_int));
+    builtins.insert(String::from("颗粒度"), Object::Builtin(1, pua_float));
+    builtins.insert(String::from("拆解"), Object::Builtin(-1, pua_split));
+    builtins.insert(String::from("整合"), Object::Builtin(-1, pua_join));
+    builtins.insert(String::from("瘦身"), Object::Builtin(1, pua_trim));
+    builtins.insert(String::from("拔高"), Object::Builtin(1, pua_upper));
+    builtins.insert(String::from("下沉"), Object::Builtin(1, pua_lower));
+    builtins.insert(String::from("置换"), Object::Builtin(3, pua_replace));
+    builtins.insert(String::from("涵盖"), Object::Builtin(2, pua_contains));
+    builtins.insert(String::from("开局"), Object::Builtin(2, pua_starts_with));
+    builtins.insert(String::from("收尾"), Object::Builtin(2, pua_ends_with));
+    builtins.insert(String::from("定位"), Object::Builtin(2, pua_find));
+    builtins.insert(String::from("打散"), Object::Builtin(1, pua_chars));
+    builtins.insert(String::from("复制"), Object::Builtin(2, pua_repeat));
+    builtins.insert(String::from("转化"), Object::Builtin(2, pua_map));
+    builtins.insert(String::from("优化"), Object::Builtin(2, pua_filter));
+    builtins.insert(String::from("收敛"), Object::Builtin(-1, pua_reduce));
+    builtins.insert(String::from("排名"), Object::Builtin(-1, pua_sort));
+    builtins.insert(String::from("赛马"), Object::Builtin(2, pua_sort_by));
+    builtins.insert(String::from("亮点"), Object::Builtin(-1, pua_any));
+    builtins.insert(String::from("全链路"), Object::Builtin(-1, pua_all));
+    builtins.insert(String::from("打通"), Object::Builtin(-1, pua_zip));
+    builtins.insert(String::from("盘点"), Object::Builtin(1, pua_enumerate));
+    builtins.insert(String::from("复盘"), Object::Builtin(1, pua_reverse));
+    builtins.insert(String::from("扁平化"), Object::Builtin(-1, pua_flatten));
+    builtins.insert(String::from("差异化"), Object::Builtin(1, pua_unique));
     builtins
 }
 
-fn monkey_len(args: Vec<Object>) -> Object {
+/// `len(s)` counts the characters of a string, and `len(s, "grapheme")`
+/// what a reader would see as characters, such as an emoji with a skin tone.
+fn monkey_len(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[..] {
+        [_] => {}
+        [Object::String(_), Object::String(mode)] if mode == "grapheme" => {}
+        [Object::String(_), mode] => {
+            return Object::Error(format!(
+                "unknown mode for `len`: {}, want \"grapheme\"",
+                mode
+            ))
+        }
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `len` (want 1 value or a string and a mode, got {:?})",
+                args
+            ))
+        }
+    }
+
     match &args[0] {
-        Object::String(s) => Object::Int(s.len() as i64),
+        Object::String(s) if args.len() == 2 => Object::Int(s.graphemes(true).count() as i64),
+        Object::String(s) => Object::Int(s.chars().count() as i64),
         Object::Array(o) => Object::Int(o.len() as i64),
+        Object::Range(start, end, step) => match Object::range_len(*start, *end, *step) {
+            len if len <= i128::from(i64::MAX) => Object::Int(len as i64),
+            len => Object::BigInt(
+                &BigInt::from((len >> 1) as i64).shift_left(1) + &BigInt::from((len & 1) as i64),
+            ),
+        },
         o => Object::Error(format!("argument to `len` not supported, got {}", o)),
     }
 }
 
-fn monkey_first(args: Vec<Object>) -> Object {
+fn monkey_first(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match &args[0] {
         Object::Array(o) => {
             if let Some(ao) = o.first() {
@@ -49,7 +138,7 @@ fn monkey_first(args: Vec<Object>) -> Object {
     }
 }
 
-fn monkey_last(args: Vec<Object>) -> Object {
+fn monkey_last(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match &args[0] {
         Object::Array(o) => {
             if let Some(ao) = o.last() {
@@ -62,7 +151,7 @@ fn monkey_last(args: Vec<Object>) -> Object {
     }
 }
 
-fn monkey_rest(args: Vec<Object>) -> Object {
+fn monkey_rest(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match &args[0] {
         Object::Array(o) => {
             if !o.is_empty() {
@@ -75,7 +164,7 @@ fn monkey_rest(args: Vec<Object>) -> Object {
     }
 }
 
-fn monkey_push(args: Vec<Object>) -> Object {
+fn monkey_push(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match &args[0] {
         Object::Array(o) => {
             let mut arr = o.clone();
@@ -86,18 +175,15 @@ fn monkey_push(args: Vec<Object>) -> Object {
     }
 }
 
-fn pua_str(args: Vec<Object>) -> Object {
-    match &args[0] {
-        Object::String(s) => Object::String(s.to_string()),
-        x => Object::String(format!("{}", x)),
-    }
+fn pua_str(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    Object::String(args[0].str_value())
 }
 
-fn pua_repr(args: Vec<Object>) -> Object {
+fn pua_repr(_: &mut Evaluator, args: Vec<Object>) -> Object {
     Object::String(format!("{}", args[0]))
 }
 
-fn pua_print(args: Vec<Object>) -> Object {
+fn pua_print(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match &args[0] {
         Object::String(ref o) => {
             println!("{}", o);
@@ -107,18 +193,22 @@ fn pua_print(args: Vec<Object>) -> Object {
     }
 }
 
-fn pua_output(args: Vec<Object>) -> Object {
+fn pua_output(_: &mut Evaluator, args: Vec<Object>) -> Object {
     for arg in args {
         println!("{}", arg);
     }
     Object::Null
 }
 
-fn pua_quit(args: Vec<Object>) -> Object {
+fn pua_quit(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match args.len() {
         0 => std::process::exit(0),
         1 => match &args[0] {
-            Object::Int(i) => std::process::exit(*i as i32),
+            Object::Int(i @ 0..=255) => std::process::exit(*i as i32),
+            Object::Int(i) => Object::Error(format!(
+                "exit status for `quit` must be between 0 and 255. got {}",
+                i
+            )),
             o => Object::Error(format!("argument to `quit` must be int. got {}", o)),
         },
         _ => Object::Error(format!(
@@ -128,9 +218,9 @@ fn pua_quit(args: Vec<Object>) -> Object {
     }
 }
 
-fn pua_atoi(args: Vec<Object>) -> Object {
+fn pua_atoi(_: &mut Evaluator, args: Vec<Object>) -> Object {
     match &args[..] {
-        [Object::String(s)] => s.parse().map(Object::Int).unwrap_or_else(|_| {
+        [Object::String(s)] => s.parse().map(Object::from_big_int).unwrap_or_else(|_| {
             Object::Error(format!(
                 "argument to `atoi` must be valid digits. got {:?}",
                 s
@@ -142,3 +232,543 @@ fn pua_atoi(args: Vec<Object>) -> Object {
         )),
     }
 }
+
+fn pua_range(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    let (start, end, step) = match &args[..] {
+        [Object::Int(end)] => (0, *end, 1),
+        [Object::Int(start), Object::Int(end)] => (*start, *end, 1),
+        [Object::Int(start), Object::Int(end), Object::Int(step)] => (*start, *end, *step),
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `range` (want 1 to 3 ints, got {:?})",
+                args
+            ))
+        }
+    };
+
+    if step == 0 {
+        return Object::Error(String::from("`range` step must not be zero"));
+    }
+
+    Object::Range(start, end, step)
+}
+
+fn pua_int(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Int(i) => Object::Int(*i),
+        Object::BigInt(i) => Object::BigInt(i.clone()),
+        // `{:.0}` prints every digit of a whole float, however large.
+        Object::Float(f) if f.is_finite() => format!("{:.0}", f.trunc())
+            .parse()
+            .map(Object::from_big_int)
+            .unwrap_or(Object::Null),
+        Object::Bool(b) => Object::Int(*b as i64),
+        Object::String(s) => s
+            .trim()
+            .parse()
+            .map(Object::from_big_int)
+            .unwrap_or_else(|_| {
+                Object::Error(format!(
+                    "argument to `int` must be valid digits. got {:?}",
+                    s
+                ))
+            }),
+        o => Object::Error(format!("argument to `int` not supported, got {}", o)),
+    }
+}
+
+fn pua_float(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::Int(i) => Object::Float(*i as f64),
+        Object::BigInt(i) => Object::Float(i.to_f64()),
+        Object::Float(f) => Object::Float(*f),
+        Object::String(s) => s.trim().parse().map(Object::Float).unwrap_or_else(|_| {
+            Object::Error(format!("argument to `float` must be a number. got {:?}", s))
+        }),
+        o => Object::Error(format!("argument to `float` not supported, got {}", o)),
+    }
+}
+
+/// A string argument. Chars are accepted wherever a string is.
+fn string_arg(name: &str, arg: &Object) -> Result<String, Object> {
+    match arg {
+        Object::String(s) => Ok(s.clone()),
+        Object::Char(c) => Ok(c.to_string()),
+        o => Err(Object::Error(format!(
+            "argument to `{}` must be string. got {}",
+            name, o
+        ))),
+    }
+}
+
+fn pua_split(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    let parts: Vec<String> = match &args[..] {
+        [Object::String(s)] => s.split_whitespace().map(String::from).collect(),
+        [Object::String(s), sep] => match string_arg("split", sep) {
+            Ok(ref sep) if sep.is_empty() => s.chars().map(String::from).collect(),
+            Ok(sep) => s.split(sep.as_str()).map(String::from).collect(),
+            Err(err) => return err,
+        },
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `split` (want a string and an optional separator, got {:?})",
+                args
+            ))
+        }
+    };
+
+    Object::Array(parts.into_iter().map(Object::String).collect())
+}
+
+fn pua_join(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    let (items, sep) = match &args[..] {
+        [Object::Array(items)] => (items, String::new()),
+        [Object::Array(items), sep] => match string_arg("join", sep) {
+            Ok(sep) => (items, sep),
+            Err(err) => return err,
+        },
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `join` (want an array and an optional separator, got {:?})",
+                args
+            ))
+        }
+    };
+
+    Object::String(
+        items
+            .iter()
+            .map(Object::str_value)
+            .collect::<Vec<_>>()
+            .join(&sep),
+    )
+}
+
+fn pua_trim(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => Object::String(s.trim().to_string()),
+        o => Object::Error(format!("argument to `trim` must be string. got {}", o)),
+    }
+}
+
+fn pua_upper(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => Object::String(s.to_uppercase()),
+        Object::Char(c) => Object::String(c.to_uppercase().collect()),
+        o => Object::Error(format!("argument to `upper` must be string. got {}", o)),
+    }
+}
+
+fn pua_lower(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => Object::String(s.to_lowercase()),
+        Object::Char(c) => Object::String(c.to_lowercase().collect()),
+        o => Object::Error(format!("argument to `lower` must be string. got {}", o)),
+    }
+}
+
+fn pua_replace(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => match (
+            string_arg("replace", &args[1]),
+            string_arg("replace", &args[2]),
+        ) {
+            (Ok(from), Ok(to)) => Object::String(s.replace(&from, &to)),
+            (Err(err), _) | (_, Err(err)) => err,
+        },
+        o => Object::Error(format!("argument to `replace` must be string. got {}", o)),
+    }
+}
+
+fn pua_contains(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => match string_arg("contains", &args[1]) {
+            Ok(sub) => Object::Bool(s.contains(&sub)),
+            Err(err) => err,
+        },
+        o => Object::Error(format!("argument to `contains` must be string. got {}", o)),
+    }
+}
+
+fn pua_starts_with(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => match string_arg("starts_with", &args[1]) {
+            Ok(prefix) => Object::Bool(s.starts_with(&prefix)),
+            Err(err) => err,
+        },
+        o => Object::Error(format!(
+            "argument to `starts_with` must be string. got {}",
+            o
+        )),
+    }
+}
+
+fn pua_ends_with(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => match string_arg("ends_with", &args[1]) {
+            Ok(suffix) => Object::Bool(s.ends_with(&suffix)),
+            Err(err) => err,
+        },
+        o => Object::Error(format!("argument to `ends_with` must be string. got {}", o)),
+    }
+}
+
+/// The index, in characters, of the first occurrence, or null.
+fn pua_find(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => match string_arg("find", &args[1]) {
+            Ok(sub) => match s.find(&sub) {
+                Some(at) => Object::Int(s[..at].chars().count() as i64),
+                None => Object::Null,
+            },
+            Err(err) => err,
+        },
+        o => Object::Error(format!("argument to `find` must be string. got {}", o)),
+    }
+}
+
+fn pua_chars(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => Object::Array(s.chars().map(Object::Char).collect()),
+        o => Object::Error(format!("argument to `chars` must be string. got {}", o)),
+    }
+}
+
+fn pua_repeat(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    let (s, n) = match (&args[0], &args[1]) {
+        (Object::String(s), Object::Int(n)) => (s.clone(), *n),
+        (Object::Char(c), Object::Int(n)) => (c.to_string(), *n),
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `repeat` (want a string and an int, got {:?})",
+                args
+            ))
+        }
+    };
+
+    match Object::repeat_len(s.len(), n) {
+        Some(_) => Object::String(s.repeat(n.max(0) as usize)),
+        None => Object::Error(format!(
+            "repetition too large: {} * {}",
+            Object::String(s),
+            n
+        )),
+    }
+}
+
+/// The items of an array, a range or a string (as chars), for the builtins
+/// that need all of them at once. Ranges too long to build are refused.
+fn items_arg(name: &str, arg: &Object) -> Result<Vec<Object>, Object> {
+    if let Object::Range(start, end, step) = arg {
+        if Object::range_len(*start, *end, *step) > MAX_BUILD_LEN as i128 {
+            return Err(Object::Error(format!(
+                "range too large for `{}`: {}",
+                name, arg
+            )));
+        }
+    }
+    items_iter(name, arg).map(Iterator::collect)
+}
+
+/// Like `items_arg`, but yields the items one at a time, so a huge range
+/// is never collected.
+fn items_iter<'a>(
+    name: &str,
+    arg: &'a Object,
+) -> Result<Box<dyn Iterator<Item = Object> + 'a>, Object> {
+    match arg {
+        Object::Array(items) => Ok(Box::new(items.iter().cloned())),
+        Object::Range(start, end, step) => Ok(Box::new(
+            Object::range_values(*start, *end, *step).map(Object::Int),
+        )),
+        Object::String(s) => Ok(Box::new(s.chars().map(Object::Char))),
+        o => Err(Object::Error(format!(
+            "argument to `{}` must be array, range or string. got {}",
+            name, o
+        ))),
+    }
+}
+
+/// Calls `func` on each item, stopping at the first error.
+fn call_each<I>(evaluator: &mut Evaluator, func: &Object, items: I) -> Result<Vec<Object>, Object>
+where
+    I: IntoIterator<Item = Object>,
+{
+    items
+        .into_iter()
+        .map(|item| match evaluator.call(func.clone(), vec![item]) {
+            Object::Error(msg) => Err(Object::Error(msg)),
+            result => Ok(result),
+        })
+        .collect()
+}
+
+fn pua_map(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    let result = items_iter("map", &args[0])
+        .and_then(|items| call_each(evaluator, &args[1], items))
+        .map(Object::Array);
+
+    result.unwrap_or_else(|err| err)
+}
+
+fn pua_filter(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    let result = items_iter("filter", &args[0]).and_then(|items| {
+        let mut kept = vec![];
+        for item in items {
+            match evaluator.call(args[1].clone(), vec![item.clone()]) {
+                Object::Error(msg) => return Err(Object::Error(msg)),
+                keep => {
+                    if Evaluator::is_truthy(keep) {
+                        kept.push(item);
+                    }
+                }
+            }
+        }
+        Ok(Object::Array(kept))
+    });
+
+    result.unwrap_or_else(|err| err)
+}
+
+/// `reduce(items, f, initial)` folds with `f(acc, item)`. Without `initial`
+/// the first item starts the fold.
+fn pua_reduce(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    let (items, func, initial) = match &args[..] {
+        [items, func] => (items, func, None),
+        [items, func, initial] => (items, func, Some(initial.clone())),
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `reduce` (want items, a function and an optional initial value, got {:?})",
+                args
+            ))
+        }
+    };
+
+    let mut items = match items_iter("reduce", items) {
+        Ok(items) => items,
+        Err(err) => return err,
+    };
+    let mut acc = match initial.or_else(|| items.next()) {
+        Some(acc) => acc,
+        None => return Object::Error(String::from("`reduce` of nothing with no initial value")),
+    };
+
+    for item in items {
+        acc = evaluator.call(func.clone(), vec![acc, item]);
+        if Evaluator::is_error(&acc) {
+            break;
+        }
+    }
+
+    acc
+}
+
+/// A stable merge sort that stops at the first error. Unlike `sort_by` it
+/// does not mind a comparator that is not a total order.
+fn merge_sort<T, F>(items: Vec<T>, less: &mut F) -> Result<Vec<T>, Object>
+where
+    F: FnMut(&T, &T) -> Result<bool, Object>,
+{
+    if items.len() < 2 {
+        return Ok(items);
+    }
+
+    let mut left = items;
+    let right = left.split_off(left.len() / 2);
+    let (left, right) = (merge_sort(left, less)?, merge_sort(right, less)?);
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut left, mut right) = (left.into_iter().peekable(), right.into_iter().peekable());
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // Taking from the left on ties keeps equal items in order.
+        if less(r, l)? {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
+
+fn less_than(a: &Object, b: &Object) -> Result<bool, Object> {
+    match Evaluator::compare(a, b) {
+        Some(ordering) => Ok(ordering == Ordering::Less),
+        None => Err(Object::Error(format!("type mismatch: {} < {}", a, b))),
+    }
+}
+
+/// `sort(items)` sorts with `<`; `sort(items, f)` with a comparator that
+/// gives a negative number when its first argument goes first.
+fn pua_sort(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    let result = match &args[..] {
+        [items] => items_arg("sort", items).and_then(|items| merge_sort(items, &mut less_than)),
+        [items, func] => items_arg("sort", items).and_then(|items| {
+            merge_sort(items, &mut |a, b| match evaluator
+                .call(func.clone(), vec![a.clone(), b.clone()])
+            {
+                Object::Int(i) => Ok(i < 0),
+                Object::BigInt(i) => Ok(i.is_negative()),
+                Object::Float(f) => Ok(f < 0.0),
+                Object::Error(msg) => Err(Object::Error(msg)),
+                o => Err(Object::Error(format!(
+                    "comparator of `sort` must return a number. got {}",
+                    o
+                ))),
+            })
+        }),
+        _ => Err(Object::Error(format!(
+            "illegal arguments to `sort` (want items and an optional comparator, got {:?})",
+            args
+        ))),
+    };
+
+    result.map(Object::Array).unwrap_or_else(|err| err)
+}
+
+/// Sorts by the keys `f` gives, calling it once for each item.
+fn pua_sort_by(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    let result = items_arg("sort_by", &args[0]).and_then(|items| {
+        let keys = call_each(evaluator, &args[1], items.iter().cloned())?;
+        let pairs = keys.into_iter().zip(items).collect();
+        let sorted = merge_sort(pairs, &mut |(a, _), (b, _)| less_than(a, b))?;
+        Ok(Object::Array(
+            sorted.into_iter().map(|(_, item)| item).collect(),
+        ))
+    });
+
+    result.unwrap_or_else(|err| err)
+}
+
+/// Whether `f` (or, without one, the item itself) is truthy for any or for
+/// all of the items, stopping as soon as the answer is known.
+fn any_or_all(evaluator: &mut Evaluator, name: &str, args: &[Object], all: bool) -> Object {
+    let (items, func) = match args {
+        [items] => (items, None),
+        [items, func] => (items, Some(func)),
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `{}` (want items and an optional function, got {:?})",
+                name, args
+            ))
+        }
+    };
+    let items = match items_iter(name, items) {
+        Ok(items) => items,
+        Err(err) => return err,
+    };
+
+    for item in items {
+        let result = match func {
+            Some(func) => evaluator.call(func.clone(), vec![item]),
+            None => item,
+        };
+        if Evaluator::is_error(&result) {
+            return result;
+        }
+        if Evaluator::is_truthy(result) != all {
+            return Object::Bool(!all);
+        }
+    }
+
+    Object::Bool(all)
+}
+
+fn pua_any(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    any_or_all(evaluator, "any", &args, false)
+}
+
+fn pua_all(evaluator: &mut Evaluator, args: Vec<Object>) -> Object {
+    any_or_all(evaluator, "all", &args, true)
+}
+
+/// Pairs up the items of its arguments, stopping at the shortest.
+fn pua_zip(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    if args.is_empty() {
+        return Object::Error(String::from("`zip` needs at least one argument"));
+    }
+
+    let mut lists = match args
+        .iter()
+        .map(|arg| items_iter("zip", arg))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(lists) => lists,
+        Err(err) => return err,
+    };
+
+    let mut zipped = vec![];
+    while let Some(items) = lists.iter_mut().map(Iterator::next).collect() {
+        zipped.push(Object::Array(items));
+    }
+    Object::Array(zipped)
+}
+
+fn pua_enumerate(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match items_iter("enumerate", &args[0]) {
+        Ok(items) => Object::Array(
+            items
+                .enumerate()
+                .map(|(i, item)| Object::Array(vec![Object::Int(i as i64), item]))
+                .collect(),
+        ),
+        Err(err) => err,
+    }
+}
+
+fn pua_reverse(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match &args[0] {
+        Object::String(s) => Object::String(s.chars().rev().collect()),
+        arg => match items_arg("reverse", arg) {
+            Ok(items) => Object::Array(items.into_iter().rev().collect()),
+            Err(err) => err,
+        },
+    }
+}
+
+/// `flatten(items)` flattens one level of nested arrays, and
+/// `flatten(items, depth)` that many levels.
+fn pua_flatten(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    fn flatten(items: Vec<Object>, depth: i64, flat: &mut Vec<Object>) {
+        for item in items {
+            match item {
+                Object::Array(inner) if depth > 0 => flatten(inner, depth - 1, flat),
+                item => flat.push(item),
+            }
+        }
+    }
+
+    let (items, depth) = match &args[..] {
+        [Object::Array(items)] => (items, 1),
+        [Object::Array(items), Object::Int(depth)] => (items, *depth),
+        _ => {
+            return Object::Error(format!(
+                "illegal arguments to `flatten` (want an array and an optional depth, got {:?})",
+                args
+            ))
+        }
+    };
+
+    let mut flat = vec![];
+    flatten(items.clone(), depth, &mut flat);
+    Object::Array(flat)
+}
+
+/// The items without repeats, keeping the first of each, compared as `==`
+/// does.
+fn pua_unique(_: &mut Evaluator, args: Vec<Object>) -> Object {
+    match items_arg("unique", &args[0]) {
+        Ok(items) => {
+            let mut unique: Vec<Object> = vec![];
+            for item in items {
+                if !unique.iter().any(|u| Evaluator::values_equal(u, &item)) {
+                    unique.push(item);
+                }
+            }
+            Object::Array(unique)
+        }
+        Err(err) => err,
+    }
+}
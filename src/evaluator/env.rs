@@ -9,6 +9,12 @@ pub struct Env {
     outer: Option<Rc<RefCell<Env>>>,
 }
 
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Env {
     pub fn new() -> Self {
         Env {
@@ -38,7 +44,30 @@ impl Env {
         }
     }
 
+    /// Names bound in this scope and all enclosing ones.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self.store.keys().cloned().collect::<Vec<_>>();
+        if let Some(ref outer) = self.outer {
+            names.extend(outer.borrow().names());
+        }
+        names
+    }
+
     pub fn set(&mut self, name: String, value: &Object) {
         self.store.insert(name, value.clone());
     }
+
+    /// Rebinds `name` in the scope that defined it. Returns false if it is
+    /// not bound anywhere.
+    pub fn assign(&mut self, name: String, value: &Object) -> bool {
+        if self.store.contains_key(&name) {
+            self.set(name, value);
+            true
+        } else {
+            match self.outer {
+                Some(ref outer) => outer.borrow_mut().assign(name, value),
+                None => false,
+            }
+        }
+    }
 }
@@ -3,25 +3,55 @@ pub mod env;
 pub mod object;
 
 use ast::*;
+use bigint::BigInt;
 use evaluator::env::*;
 use evaluator::object::*;
+use span::Span;
 use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::rc::Rc;
 
+/// What reading an array or string at an index past its end gives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutOfRange {
+    Null,
+    Error,
+}
+
 #[derive(Debug)]
 pub struct Evaluator {
     pub env: Rc<RefCell<Env>>,
+    error_span: Option<Span>,
+    out_of_range: OutOfRange,
 }
 
 impl Evaluator {
     pub fn new(env: Rc<RefCell<Env>>) -> Self {
-        Evaluator { env }
+        Evaluator {
+            env,
+            error_span: None,
+            out_of_range: OutOfRange::Null,
+        }
+    }
+
+    /// Out-of-range indices give null unless this is set to
+    /// `OutOfRange::Error`. Slices never go out of range.
+    pub fn set_out_of_range(&mut self, out_of_range: OutOfRange) {
+        self.out_of_range = out_of_range;
+    }
+
+    /// Span of the innermost expression that produced the error returned by
+    /// the last call to `eval`, if any.
+    pub fn error_span(&self) -> Option<Span> {
+        self.error_span
     }
 
     fn is_truthy(obj: Object) -> bool {
         match obj {
             Object::Null | Object::Bool(false) | Object::Int(325) => false,
+            Object::Float(3.25) => false,
             _ => true,
         }
     }
@@ -38,17 +68,36 @@ impl Evaluator {
     }
 
     pub fn eval(&mut self, program: &Program) -> Option<Object> {
+        self.eval_with(program, |_, _| {})
+    }
+
+    /// Like `eval`, but hands the value of each top-level statement that has
+    /// one to `on_value` as it goes.
+    pub fn eval_with(
+        &mut self,
+        program: &Program,
+        mut on_value: impl FnMut(&mut Self, &Object),
+    ) -> Option<Object> {
         let mut result = None;
 
         for stmt in program {
-            if *stmt == Stmt::Blank {
+            if stmt.is_trivia() {
                 continue;
             }
 
+            self.error_span = None;
+
             match self.eval_stmt(stmt) {
-                Some(Object::ReturnValue(value)) => return Some(*value),
+                Some(Object::ReturnValue(value)) => {
+                    on_value(self, &value);
+                    return Some(*value);
+                }
                 Some(Object::Error(msg)) => return Some(Object::Error(msg)),
-                obj => result = obj,
+                Some(value) => {
+                    on_value(self, &value);
+                    result = Some(value);
+                }
+                None => result = None,
             }
         }
 
@@ -59,7 +108,7 @@ impl Evaluator {
         let mut result = None;
 
         for stmt in stmts {
-            if *stmt == Stmt::Blank {
+            if stmt.is_trivia() {
                 continue;
             }
 
@@ -73,11 +122,14 @@ impl Evaluator {
         result
     }
 
-    fn eval_block_stmt_with_continue_and_break_statement(&mut self, stmts: &BlockStmt) -> Option<Object> {
+    fn eval_block_stmt_with_continue_and_break_statement(
+        &mut self,
+        stmts: &BlockStmt,
+    ) -> Option<Object> {
         let mut result = None;
 
         for stmt in stmts {
-            if *stmt == Stmt::Blank {
+            if stmt.is_trivia() {
                 continue;
             }
 
@@ -94,12 +146,9 @@ impl Evaluator {
     }
 
     fn eval_stmt(&mut self, stmt: &Stmt) -> Option<Object> {
-        match stmt {
-            Stmt::Let(ident, expr) => {
-                let value = match self.eval_expr(expr) {
-                    Some(value) => value,
-                    None => return None,
-                };
+        match &stmt.kind {
+            StmtKind::Let(ident, expr) => {
+                let value = self.eval_expr(expr)?;
                 if Self::is_error(&value) {
                     Some(value)
                 } else {
@@ -108,14 +157,19 @@ impl Evaluator {
                     None
                 }
             }
-            Stmt::Break => Some(Object::BreakStatement),
-            Stmt::Continue => Some(Object::ContinueStatement),
-            Stmt::Expr(expr) => self.eval_expr(expr),
-            Stmt::Return(expr) => {
-                let value = match self.eval_expr(expr) {
-                    Some(value) => value,
-                    None => return None,
-                };
+            StmtKind::Assign { target, op, value } => {
+                let value = self.eval_expr(value)?;
+                if Self::is_error(&value) {
+                    Some(value)
+                } else {
+                    self.eval_assign(target, op, value)
+                }
+            }
+            StmtKind::Break => Some(Object::BreakStatement),
+            StmtKind::Continue => Some(Object::ContinueStatement),
+            StmtKind::Expr(expr) => self.eval_expr(expr),
+            StmtKind::Return(expr) => {
+                let value = self.eval_expr(expr)?;
                 if Self::is_error(&value) {
                     Some(value)
                 } else {
@@ -126,44 +180,211 @@ impl Evaluator {
         }
     }
 
+    /// Stores `value` into `target`, combining it with the current value
+    /// through `op` first when set. The target's indices are evaluated once;
+    /// assigning to an element rebuilds the enclosing arrays or hashes and
+    /// assigns the outermost one to the name it is bound to.
+    fn eval_assign(&mut self, target: &Expr, op: &Option<Infix>, value: Object) -> Option<Object> {
+        let (name, indices) = match self.eval_assign_target(target)? {
+            Ok(place) => place,
+            Err(error) => return Some(error),
+        };
+
+        let result = self.eval_assign_place(name, &indices, op, value)?;
+
+        if self.error_span.is_none() {
+            self.error_span = Some(target.span);
+        }
+
+        Some(result)
+    }
+
+    /// Resolves an assignment target to the name it is bound to and the
+    /// evaluated indices leading from it to the assigned element.
+    fn eval_assign_target(
+        &mut self,
+        target: &Expr,
+    ) -> Option<Result<(String, Vec<Object>), Object>> {
+        match &target.kind {
+            ExprKind::Ident(Ident(name)) => Some(Ok((name.clone(), vec![]))),
+            ExprKind::Index(left_expr, index_expr) => {
+                let (name, mut indices) = match self.eval_assign_target(left_expr)? {
+                    Ok(place) => place,
+                    Err(error) => return Some(Err(error)),
+                };
+                let index = self.eval_expr(index_expr)?;
+                if Self::is_error(&index) {
+                    return Some(Err(index));
+                }
+                indices.push(index);
+                Some(Ok((name, indices)))
+            }
+            _ => {
+                if self.error_span.is_none() {
+                    self.error_span = Some(target.span);
+                }
+                Some(Err(Self::error(String::from("invalid assignment target"))))
+            }
+        }
+    }
+
+    fn eval_assign_place(
+        &mut self,
+        name: String,
+        indices: &[Object],
+        op: &Option<Infix>,
+        value: Object,
+    ) -> Option<Object> {
+        if indices.is_empty() && op.is_none() {
+            if self.env.borrow_mut().assign(name.clone(), &value) {
+                return None;
+            }
+            return Some(Self::error(format!("identifier not found: {}", name)));
+        }
+
+        let root = self.eval_ident(&Ident(name.clone()));
+        if Self::is_error(&root) {
+            return Some(root);
+        }
+
+        let value = match op {
+            Some(op) => {
+                let mut current = root.clone();
+                for index in indices {
+                    current = self.eval_index_expr(current, index.clone());
+                    if Self::is_error(&current) {
+                        return Some(current);
+                    }
+                }
+                self.eval_infix_expr(op, current, value)
+            }
+            None => value,
+        };
+
+        match self.eval_nested_assign(root, indices, value) {
+            error @ Object::Error(_) => Some(error),
+            updated => {
+                self.env.borrow_mut().assign(name, &updated);
+                None
+            }
+        }
+    }
+
+    /// Returns `container` with the element at `indices` replaced by `value`.
+    fn eval_nested_assign(
+        &mut self,
+        container: Object,
+        indices: &[Object],
+        value: Object,
+    ) -> Object {
+        let (index, rest) = match indices.split_first() {
+            Some(split) => split,
+            None => return value,
+        };
+        let value = if rest.is_empty() {
+            value
+        } else {
+            let element = self.eval_index_expr(container.clone(), index.clone());
+            if Self::is_error(&element) {
+                return element;
+            }
+            self.eval_nested_assign(element, rest, value)
+        };
+        if Self::is_error(&value) {
+            return value;
+        }
+        self.eval_index_assign(container, index.clone(), value)
+    }
+
+    fn eval_index_assign(&mut self, left: Object, index: Object, value: Object) -> Object {
+        match (left, index) {
+            (error @ Object::Error(_), _) | (_, error @ Object::Error(_)) => error,
+            (Object::Array(mut array), Object::Int(i)) => match Self::resolve_index(i, array.len())
+            {
+                Some(i) => {
+                    array[i] = value;
+                    Object::Array(array)
+                }
+                None => Self::error(format!(
+                    "index out of range: {} (length {})",
+                    i,
+                    array.len()
+                )),
+            },
+            (Object::Hash(mut hash), index) => match index {
+                _ if index.is_hashable() => {
+                    hash.insert(index, value);
+                    Object::Hash(hash)
+                }
+                _ => Self::error(format!("unusable as hash key: {}", index)),
+            },
+            (left, index) => Self::error(format!(
+                "index assignment not supported: {}[{}]",
+                left, index
+            )),
+        }
+    }
+
     fn eval_expr(&mut self, expr: &Expr) -> Option<Object> {
-        match expr {
-            Expr::Ident(ident) => Some(self.eval_ident(ident)),
-            Expr::Literal(literal) => Some(self.eval_literal(literal)),
-            Expr::Prefix(prefix, right_expr) => {
-                self.eval_expr(&*right_expr).map(|right| self.eval_prefix_expr(prefix, right))
-            }
-            Expr::Infix(infix, left_expr, right_expr) => {
-                let left = self.eval_expr(&*left_expr);
-                let right = self.eval_expr(&*right_expr);
-                if left.is_some() && right.is_some() {
-                    Some(self.eval_infix_expr(infix, left.unwrap(), right.unwrap()))
-                } else {
-                    None
+        let result = match &expr.kind {
+            ExprKind::Ident(ident) => Some(self.eval_ident(ident)),
+            ExprKind::Literal(literal) => Some(self.eval_literal(literal)),
+            ExprKind::Prefix(prefix, right_expr) => self
+                .eval_expr(right_expr)
+                .map(|right| self.eval_prefix_expr(prefix, right)),
+            ExprKind::Infix(infix @ Infix::And, left_expr, right_expr)
+            | ExprKind::Infix(infix @ Infix::Or, left_expr, right_expr) => {
+                self.eval_logical_expr(infix, left_expr, right_expr)
+            }
+            ExprKind::Infix(infix, left_expr, right_expr) => {
+                let left = self.eval_expr(left_expr);
+                let right = self.eval_expr(right_expr);
+                match (left, right) {
+                    (Some(left), Some(right)) => Some(self.eval_infix_expr(infix, left, right)),
+                    _ => None,
                 }
             }
-            Expr::Index(left_expr, index_expr) => {
-                let left = self.eval_expr(&*left_expr);
-                let index = self.eval_expr(&*index_expr);
-                if left.is_some() && index.is_some() {
-                    Some(self.eval_index_expr(left.unwrap(), index.unwrap()))
-                } else {
-                    None
+            ExprKind::Index(left_expr, index_expr) => {
+                let left = self.eval_expr(left_expr);
+                let index = self.eval_expr(index_expr);
+                match (left, index) {
+                    (Some(left), Some(index)) => Some(self.eval_index_expr(left, index)),
+                    _ => None,
                 }
             }
-            Expr::If {
+            ExprKind::Slice {
+                left,
+                start,
+                end,
+                step,
+            } => self.eval_slice_expr(left, [start, end, step]),
+            ExprKind::Interpolated(parts) => Some(self.eval_interpolated_expr(parts)),
+            ExprKind::If {
                 cond,
                 consequence,
                 alternative,
-            } => self.eval_if_expr(&*cond, consequence, alternative),
-            Expr::While { cond, consequence } => self.eval_while_expr(&*cond, consequence),
-            Expr::Func { params, body } => Some(Object::Func(
+            } => self.eval_if_expr(cond, consequence, alternative),
+            ExprKind::While { cond, consequence } => self.eval_while_expr(cond, consequence),
+            ExprKind::For {
+                vars,
+                iterable,
+                body,
+            } => self.eval_for_expr(vars, iterable, body),
+            ExprKind::Func { params, body } => Some(Object::Func(
                 params.clone(),
                 body.clone(),
                 Rc::clone(&self.env),
             )),
-            Expr::Call { func, args } => Some(self.eval_call_expr(func, args)),
+            ExprKind::Call { func, args } => Some(self.eval_call_expr(func, args)),
+        };
+
+        if let Some(Object::Error(_)) = result {
+            if self.error_span.is_none() {
+                self.error_span = Some(expr.span);
+            }
         }
+
+        result
     }
 
     fn eval_ident(&mut self, ident: &Ident) -> Object {
@@ -180,6 +401,7 @@ impl Evaluator {
             Prefix::Not => self.eval_not_op_expr(right),
             Prefix::Minus => self.eval_minus_prefix_op_expr(right),
             Prefix::Plus => self.eval_plus_prefix_op_expr(right),
+            Prefix::BitNot => self.eval_bit_not_prefix_op_expr(right),
         }
     }
 
@@ -194,35 +416,238 @@ impl Evaluator {
 
     fn eval_minus_prefix_op_expr(&mut self, right: Object) -> Object {
         match right {
-            Object::Int(value) => Object::Int(-value),
+            Object::Int(value) => match value.checked_neg() {
+                Some(value) => Object::Int(value),
+                None => Object::BigInt(-&BigInt::from(value)),
+            },
+            Object::BigInt(value) => Object::from_big_int(-&value),
+            Object::Float(value) => Object::Float(-value),
             _ => Self::error(format!("unknown operator: -{}", right)),
         }
     }
 
+    fn eval_bit_not_prefix_op_expr(&mut self, right: Object) -> Object {
+        match right {
+            Object::Int(value) => Object::Int(!value),
+            // `!x == -x - 1` in two's complement.
+            Object::BigInt(value) => Object::from_big_int(&-&value - &BigInt::from(1)),
+            _ => Self::error(format!("unknown operator: ~{}", right)),
+        }
+    }
+
     fn eval_plus_prefix_op_expr(&mut self, right: Object) -> Object {
         match right {
             Object::Int(value) => Object::Int(value),
+            Object::BigInt(value) => Object::BigInt(value),
+            Object::Float(value) => Object::Float(value),
             _ => Self::error(format!("unknown operator: {}", right)),
         }
     }
 
     fn eval_infix_expr(&mut self, infix: &Infix, left: Object, right: Object) -> Object {
-        match left {
-            Object::Int(left_value) => {
-                if let Object::Int(right_value) = right {
-                    self.eval_infix_int_expr(infix, left_value, right_value)
-                } else {
-                    Self::error(format!("type mismatch: {} {} {}", left, infix, right))
+        if Self::is_error(&left) {
+            return left;
+        }
+        if Self::is_error(&right) {
+            return right;
+        }
+
+        match infix {
+            Infix::Equal => return Object::Bool(Self::values_equal(&left, &right)),
+            Infix::NotEqual => return Object::Bool(!Self::values_equal(&left, &right)),
+            Infix::In => return self.eval_in_expr(left, right),
+            _ => {}
+        }
+
+        match (left, right) {
+            (Object::Int(left), Object::Int(right)) => self.eval_infix_int_expr(infix, left, right),
+            (Object::BigInt(left), Object::BigInt(right)) => {
+                self.eval_infix_big_int_expr(infix, &left, &right)
+            }
+            (Object::BigInt(left), Object::Int(right)) => {
+                self.eval_infix_big_int_expr(infix, &left, &BigInt::from(right))
+            }
+            (Object::Int(left), Object::BigInt(right)) => {
+                self.eval_infix_big_int_expr(infix, &BigInt::from(left), &right)
+            }
+            // Mixing ints and floats works like it does with floats alone.
+            (Object::Float(left), Object::Float(right)) => {
+                self.eval_infix_float_expr(infix, left, right)
+            }
+            (Object::Int(left), Object::Float(right)) => {
+                self.eval_infix_float_expr(infix, left as f64, right)
+            }
+            (Object::Float(left), Object::Int(right)) => {
+                self.eval_infix_float_expr(infix, left, right as f64)
+            }
+            (Object::BigInt(left), Object::Float(right)) => {
+                self.eval_infix_float_expr(infix, left.to_f64(), right)
+            }
+            (Object::Float(left), Object::BigInt(right)) => {
+                self.eval_infix_float_expr(infix, left, right.to_f64())
+            }
+            (Object::String(s), Object::Int(n)) | (Object::Int(n), Object::String(s))
+                if *infix == Infix::Multiply =>
+            {
+                match Object::repeat_len(s.len(), n) {
+                    Some(_) => Object::String(s.repeat(n.max(0) as usize)),
+                    None => Self::error(format!(
+                        "repetition too large: {} * {}",
+                        Object::String(s),
+                        n
+                    )),
+                }
+            }
+            (Object::Array(a), Object::Int(n)) | (Object::Int(n), Object::Array(a))
+                if *infix == Infix::Multiply =>
+            {
+                match Object::repeat_len(a.len(), n) {
+                    Some(total) => Object::Array(a.iter().cloned().cycle().take(total).collect()),
+                    None => Self::error(format!(
+                        "repetition too large: {} * {}",
+                        Object::Array(a),
+                        n
+                    )),
                 }
             }
-            Object::String(left_value) => {
-                if let Object::String(right_value) = right {
-                    self.eval_infix_string_expr(infix, left_value, right_value)
+            (left @ Object::Int(_), right)
+            | (left @ Object::BigInt(_), right)
+            | (left @ Object::Float(_), right) => {
+                Self::error(format!("type mismatch: {} {} {}", left, infix, right))
+            }
+            (Object::String(left), Object::String(right)) => {
+                self.eval_infix_string_expr(infix, left, right)
+            }
+            (Object::String(left), Object::Char(right)) if *infix == Infix::Plus => {
+                Object::String(format!("{}{}", left, right))
+            }
+            (Object::Char(left), Object::String(right)) if *infix == Infix::Plus => {
+                Object::String(format!("{}{}", left, right))
+            }
+            (Object::Char(left), Object::Char(right)) => {
+                self.eval_infix_char_expr(infix, left, right)
+            }
+            (Object::String(left), right) => {
+                Self::error(format!("type mismatch: {} {} {}", left, infix, right))
+            }
+            (Object::Array(left), Object::Array(right)) => {
+                self.eval_infix_array_expr(infix, left, right)
+            }
+            (left @ Object::Array(_), right) => {
+                Self::error(format!("type mismatch: {} {} {}", left, infix, right))
+            }
+            (left, right) => Self::error(format!("unknown operator: {} {} {}", left, infix, right)),
+        }
+    }
+
+    /// `item in container`: an element of an array or range, a key of a
+    /// hash, or a substring of a string.
+    fn eval_in_expr(&mut self, item: Object, container: Object) -> Object {
+        match (item, container) {
+            (item, Object::Array(array)) => {
+                Object::Bool(array.iter().any(|o| Self::values_equal(&item, o)))
+            }
+            (key, Object::Hash(hash)) => match key {
+                _ if key.is_hashable() => Object::Bool(hash.contains_key(&key)),
+                _ => Self::error(format!("unusable as hash key: {}", key)),
+            },
+            (Object::String(sub), Object::String(s)) => Object::Bool(s.contains(&sub)),
+            (Object::Char(c), Object::String(s)) => Object::Bool(s.contains(c)),
+            (Object::Int(i), Object::Range(start, end, step)) => {
+                let within = if step > 0 {
+                    start <= i && i < end
                 } else {
-                    Self::error(format!("type mismatch: {} {} {}", left_value, infix, right))
+                    end < i && i <= start
+                };
+                Object::Bool(within && (i as i128 - start as i128) % step as i128 == 0)
+            }
+            (item, container @ Object::String(_)) | (item, container @ Object::Range(..)) => {
+                Self::error(format!("type mismatch: {} in {}", item, container))
+            }
+            (item, container) => {
+                Self::error(format!("unknown operator: {} in {}", item, container))
+            }
+        }
+    }
+
+    /// Structural equality. Numbers compare by value whatever their type,
+    /// functions are equal when they have the same parameters and body and
+    /// close over the same environment, and values of different types are
+    /// never equal.
+    fn values_equal(left: &Object, right: &Object) -> bool {
+        match (left, right) {
+            (Object::Int(_), _) | (Object::BigInt(_), _) | (Object::Float(_), _) => {
+                Self::compare(left, right) == Some(Ordering::Equal)
+            }
+            (Object::Array(left), Object::Array(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right)
+                        .all(|(a, b)| Self::values_equal(a, b))
+            }
+            (Object::Hash(left), Object::Hash(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .all(|(k, v)| right.get(k).is_some_and(|w| Self::values_equal(v, w)))
+            }
+            (Object::Func(a_params, a_body, a_env), Object::Func(b_params, b_body, b_env)) => {
+                Rc::ptr_eq(a_env, b_env) && a_params == b_params && a_body == b_body
+            }
+            _ => left == right,
+        }
+    }
+
+    /// Orders numbers by value, and strings and arrays lexicographically.
+    /// `None` when the values cannot be ordered.
+    fn compare(left: &Object, right: &Object) -> Option<Ordering> {
+        match (left, right) {
+            (Object::Int(a), Object::Int(b)) => Some(a.cmp(b)),
+            (Object::BigInt(a), Object::BigInt(b)) => Some(a.cmp(b)),
+            (Object::Int(a), Object::BigInt(b)) => Some(BigInt::from(*a).cmp(b)),
+            (Object::BigInt(a), Object::Int(b)) => Some(a.cmp(&BigInt::from(*b))),
+            (Object::String(a), Object::String(b)) => Some(a.cmp(b)),
+            (Object::Char(a), Object::Char(b)) => Some(a.cmp(b)),
+            (Object::Array(a), Object::Array(b)) => {
+                for (x, y) in a.iter().zip(b) {
+                    match Self::compare(x, y)? {
+                        Ordering::Equal => {}
+                        ordering => return Some(ordering),
+                    }
                 }
+                Some(a.len().cmp(&b.len()))
             }
-            _ => Self::error(format!("unknown operator: {} {} {}", left, infix, right)),
+            _ => Self::as_float(left)?.partial_cmp(&Self::as_float(right)?),
+        }
+    }
+
+    fn as_float(object: &Object) -> Option<f64> {
+        match object {
+            Object::Int(value) => Some(*value as f64),
+            Object::BigInt(value) => Some(value.to_f64()),
+            Object::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// `&&` and `||` only evaluate `right` when `left` does not decide the
+    /// result, and give back whichever operand did.
+    fn eval_logical_expr(&mut self, infix: &Infix, left: &Expr, right: &Expr) -> Option<Object> {
+        let left = self.eval_expr(left)?;
+        if Self::is_error(&left) {
+            return Some(left);
+        }
+
+        let decided = match infix {
+            Infix::And => !Self::is_truthy(left.clone()),
+            _ => Self::is_truthy(left.clone()),
+        };
+
+        if decided {
+            Some(left)
+        } else {
+            self.eval_expr(right)
         }
     }
 
@@ -235,8 +660,13 @@ impl Evaluator {
                     Self::error(format!("index operator not supported: {}", left))
                 }
             }
+            Object::String(ref s) => match index {
+                Object::Int(i) => self.eval_string_index_expr(s, i),
+                Object::Range(start, end, step) => self.eval_string_slice_expr(s, start, end, step),
+                _ => Self::error(format!("index operator not supported: {}", left)),
+            },
             Object::Hash(ref hash) => match index {
-                Object::Int(_) | Object::Bool(_) | Object::String(_) => match hash.get(&index) {
+                _ if index.is_hashable() => match hash.get(&index) {
                     Some(o) => o.clone(),
                     None => Object::Null,
                 },
@@ -248,67 +678,388 @@ impl Evaluator {
     }
 
     fn eval_array_index_expr(&mut self, array: Vec<Object>, index: i64) -> Object {
-        let max = array.len() as i64;
+        match Self::resolve_index(index, array.len()) {
+            Some(i) => array[i].clone(),
+            None => self.index_out_of_range(index, array.len()),
+        }
+    }
+
+    /// Where `index` points into `len` items, counting negative indices
+    /// from the end.
+    fn resolve_index(index: i64, len: usize) -> Option<usize> {
+        let index = if index < 0 {
+            index as i128 + len as i128
+        } else {
+            index as i128
+        };
+
+        if (0..len as i128).contains(&index) {
+            Some(index as usize)
+        } else {
+            None
+        }
+    }
 
-        if index < 0 || index > max {
-            return Object::Null;
+    fn index_out_of_range(&self, index: i64, len: usize) -> Object {
+        match self.out_of_range {
+            OutOfRange::Null => Object::Null,
+            OutOfRange::Error => {
+                Self::error(format!("index out of range: {} (length {})", index, len))
+            }
         }
+    }
 
-        match array.get(index as usize) {
-            Some(o) => o.clone(),
-            None => Object::Null,
+    fn eval_slice_expr(&mut self, left: &Expr, bounds: [&Option<Box<Expr>>; 3]) -> Option<Object> {
+        let left = self.eval_expr(left)?;
+        if Self::is_error(&left) {
+            return Some(left);
+        }
+
+        let mut values = [None; 3];
+        for (value, bound) in values.iter_mut().zip(bounds.iter()) {
+            *value = match bound {
+                None => None,
+                Some(expr) => match self.eval_expr(expr)? {
+                    Object::Int(i) => Some(i),
+                    // Past either end anyway, so the nearest `i64` will do.
+                    Object::BigInt(i) if i.is_negative() => Some(i64::MIN),
+                    Object::BigInt(_) => Some(i64::MAX),
+                    Object::Null => None,
+                    error @ Object::Error(_) => return Some(error),
+                    o => return Some(Self::error(format!("slice index must be int. got {}", o))),
+                },
+            };
+        }
+
+        let [start, end, step] = values;
+        let step = step.unwrap_or(1);
+        if step == 0 {
+            return Some(Self::error(String::from("slice step cannot be zero")));
+        }
+
+        Some(match left {
+            Object::Array(array) => Object::Array(
+                Self::slice_indices(array.len(), start, end, step)
+                    .into_iter()
+                    .map(|i| array[i].clone())
+                    .collect(),
+            ),
+            Object::String(s) => {
+                let chars = s.chars().collect::<Vec<_>>();
+                Object::String(
+                    Self::slice_indices(chars.len(), start, end, step)
+                        .into_iter()
+                        .map(|i| chars[i])
+                        .collect(),
+                )
+            }
+            o => Self::error(format!("slice operator not supported: {}", o)),
+        })
+    }
+
+    /// The indices `[start:end:step]` picks out of `len` items, as in Python:
+    /// negative bounds count from the end, and bounds past either end are
+    /// clamped to it.
+    fn slice_indices(len: usize, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+        let (len, step) = (len as i128, step as i128);
+        let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+        let clamp = |bound: Option<i64>, default: i128| match bound {
+            None => default,
+            Some(b) if b < 0 => (b as i128 + len).max(lower),
+            Some(b) => (b as i128).min(upper),
+        };
+        let (mut i, end) = if step > 0 {
+            (clamp(start, lower), clamp(end, upper))
+        } else {
+            (clamp(start, upper), clamp(end, lower))
+        };
+
+        let mut indices = vec![];
+        while (step > 0 && i < end) || (step < 0 && i > end) {
+            indices.push(i as usize);
+            i += step;
+        }
+        indices
+    }
+
+    /// The `index`th character of `s`, counting characters rather than bytes.
+    fn eval_string_index_expr(&mut self, s: &str, index: i64) -> Object {
+        let len = s.chars().count();
+
+        match Self::resolve_index(index, len) {
+            Some(i) => Object::Char(s.chars().nth(i).unwrap()),
+            None => self.index_out_of_range(index, len),
         }
     }
 
+    /// `s[range(start, end, step)]`: the characters at the indices of the
+    /// range that fall within `s`.
+    fn eval_string_slice_expr(&mut self, s: &str, start: i64, end: i64, step: i64) -> Object {
+        let chars = s.chars().collect::<Vec<_>>();
+        let len = chars.len() as i64;
+        // Moved inside `s` first, keeping to the step, so that a huge range
+        // does not take forever.
+        let (first, last, stride) = (start as i128, (len - 1) as i128, (step as i128).abs());
+        let (start, end) = if step > 0 && first < 0 {
+            (
+                (first + (-first + stride - 1) / stride * stride) as i64,
+                end.min(len),
+            )
+        } else if step > 0 {
+            (start, end.min(len))
+        } else if first > last {
+            (
+                (first - (first - last + stride - 1) / stride * stride) as i64,
+                end.max(-1),
+            )
+        } else {
+            (start, end.max(-1))
+        };
+
+        Object::String(
+            Object::range_values(start, end, step)
+                .map(|i| chars[i as usize])
+                .collect(),
+        )
+    }
+
     fn eval_infix_int_expr(&mut self, infix: &Infix, left: i64, right: i64) -> Object {
         match infix {
-            Infix::Plus => Object::Int(left + right),
-            Infix::Minus => Object::Int(left - right),
-            Infix::Multiply => Object::Int(left * right),
-            Infix::Divide => Object::Int(left / right),
+            Infix::Plus => self.int_or_big_int(infix, left, right, left.checked_add(right)),
+            Infix::Minus => self.int_or_big_int(infix, left, right, left.checked_sub(right)),
+            Infix::Multiply => self.int_or_big_int(infix, left, right, left.checked_mul(right)),
+            Infix::Divide if right == 0 => {
+                Self::error(format!("division by zero: {} / {}", left, right))
+            }
+            Infix::Divide => self.int_or_big_int(infix, left, right, left.checked_div(right)),
+            Infix::Modulo if right == 0 => {
+                Self::error(format!("division by zero: {} % {}", left, right))
+            }
+            Infix::Modulo => self.int_or_big_int(infix, left, right, left.checked_rem(right)),
+            Infix::Power if right < 0 => Object::Float((left as f64).powf(right as f64)),
+            Infix::Power => {
+                let result = u32::try_from(right)
+                    .ok()
+                    .and_then(|exp| left.checked_pow(exp));
+                self.int_or_big_int(infix, left, right, result)
+            }
+            Infix::BitAnd => Object::Int(left & right),
+            Infix::BitOr => Object::Int(left | right),
+            Infix::BitXor => Object::Int(left ^ right),
+            Infix::ShiftLeft | Infix::ShiftRight if right < 0 => Self::error(format!(
+                "negative shift amount: {} {} {}",
+                left, infix, right
+            )),
+            Infix::ShiftLeft => {
+                // Shifting back must give `left` again, or bits were lost.
+                let result = Some(right)
+                    .filter(|&right| right < 64)
+                    .map(|right| left << right)
+                    .filter(|value| value >> right == left);
+                self.int_or_big_int(infix, left, right, result)
+            }
+            Infix::ShiftRight => Object::Int(left >> right.min(63)),
+            Infix::LessThan => Object::Bool(left < right),
+            Infix::LessThanEqual => Object::Bool(left <= right),
+            Infix::GreaterThan => Object::Bool(left > right),
+            Infix::GreaterThanEqual => Object::Bool(left >= right),
+            Infix::Equal => Object::Bool(left == right),
+            Infix::NotEqual => Object::Bool(left != right),
+            // Short-circuited in `eval_logical_expr` or handled in
+            // `eval_infix_expr`.
+            Infix::And | Infix::Or | Infix::In => {
+                Self::error(format!("unknown operator: {} {} {}", left, infix, right))
+            }
+        }
+    }
+
+    /// `result` is the checked result of `left infix right`. On overflow the
+    /// operation is redone with big integers.
+    fn int_or_big_int(
+        &mut self,
+        infix: &Infix,
+        left: i64,
+        right: i64,
+        result: Option<i64>,
+    ) -> Object {
+        match result {
+            Some(value) => Object::Int(value),
+            None => self.eval_infix_big_int_expr(infix, &BigInt::from(left), &BigInt::from(right)),
+        }
+    }
+
+    fn eval_infix_big_int_expr(&mut self, infix: &Infix, left: &BigInt, right: &BigInt) -> Object {
+        match infix {
+            Infix::Plus => Object::from_big_int(left + right),
+            Infix::Minus => Object::from_big_int(left - right),
+            Infix::Multiply => Object::from_big_int(left * right),
+            Infix::Divide => match left.checked_div(right) {
+                Some(value) => Object::from_big_int(value),
+                None => Self::error(format!("division by zero: {} / {}", left, right)),
+            },
+            Infix::Modulo => match left.checked_rem(right) {
+                Some(value) => Object::from_big_int(value),
+                None => Self::error(format!("division by zero: {} % {}", left, right)),
+            },
+            Infix::Power | Infix::ShiftLeft | Infix::ShiftRight => {
+                match right.to_i64().and_then(|n| u32::try_from(n).ok()) {
+                    Some(n) => Object::from_big_int(match infix {
+                        Infix::Power => left.pow(n),
+                        Infix::ShiftLeft => left.shift_left(n),
+                        _ => left.shift_right(n),
+                    }),
+                    None if *infix == Infix::Power && right.is_negative() => {
+                        Object::Float(left.to_f64().powf(right.to_f64()))
+                    }
+                    None if right.is_negative() => Self::error(format!(
+                        "negative shift amount: {} {} {}",
+                        left, infix, right
+                    )),
+                    None => Self::error(format!("operand too large: {} {} {}", left, infix, right)),
+                }
+            }
+            Infix::LessThan => Object::Bool(left < right),
+            Infix::LessThanEqual => Object::Bool(left <= right),
+            Infix::GreaterThan => Object::Bool(left > right),
+            Infix::GreaterThanEqual => Object::Bool(left >= right),
+            Infix::Equal => Object::Bool(left == right),
+            Infix::NotEqual => Object::Bool(left != right),
+            Infix::BitAnd | Infix::BitOr | Infix::BitXor => Self::error(format!(
+                "bitwise operators need i64 operands: {} {} {}",
+                left, infix, right
+            )),
+            Infix::And | Infix::Or | Infix::In => {
+                Self::error(format!("unknown operator: {} {} {}", left, infix, right))
+            }
+        }
+    }
+
+    fn eval_infix_float_expr(&mut self, infix: &Infix, left: f64, right: f64) -> Object {
+        match infix {
+            Infix::Plus => Object::Float(left + right),
+            Infix::Minus => Object::Float(left - right),
+            Infix::Multiply => Object::Float(left * right),
+            Infix::Divide => Object::Float(left / right),
+            Infix::Modulo => Object::Float(left % right),
+            Infix::Power => Object::Float(left.powf(right)),
             Infix::LessThan => Object::Bool(left < right),
             Infix::LessThanEqual => Object::Bool(left <= right),
             Infix::GreaterThan => Object::Bool(left > right),
             Infix::GreaterThanEqual => Object::Bool(left >= right),
             Infix::Equal => Object::Bool(left == right),
             Infix::NotEqual => Object::Bool(left != right),
+            _ => Self::error(format!(
+                "unknown operator: {:?} {} {:?}",
+                left, infix, right
+            )),
         }
     }
 
     fn eval_infix_string_expr(&mut self, infix: &Infix, left: String, right: String) -> Object {
         match infix {
             Infix::Plus => Object::String(format!("{}{}", left, right)),
+            Infix::LessThan => Object::Bool(left < right),
+            Infix::LessThanEqual => Object::Bool(left <= right),
+            Infix::GreaterThan => Object::Bool(left > right),
+            Infix::GreaterThanEqual => Object::Bool(left >= right),
+            _ => Object::Error(format!("unknown operator: {} {} {}", left, infix, right)),
+        }
+    }
+
+    fn eval_infix_char_expr(&mut self, infix: &Infix, left: char, right: char) -> Object {
+        match infix {
+            Infix::LessThan => Object::Bool(left < right),
+            Infix::LessThanEqual => Object::Bool(left <= right),
+            Infix::GreaterThan => Object::Bool(left > right),
+            Infix::GreaterThanEqual => Object::Bool(left >= right),
             _ => Object::Error(format!(
-                "unknown operator: {} {} {}",
+                "unknown operator: {:?} {} {:?}",
                 left, infix, right
             )),
         }
     }
 
+    fn eval_infix_array_expr(
+        &mut self,
+        infix: &Infix,
+        left: Vec<Object>,
+        right: Vec<Object>,
+    ) -> Object {
+        if *infix == Infix::Plus {
+            return Object::Array(left.into_iter().chain(right).collect());
+        }
+
+        let (left, right) = (Object::Array(left), Object::Array(right));
+        let ordering = match infix {
+            Infix::LessThan
+            | Infix::LessThanEqual
+            | Infix::GreaterThan
+            | Infix::GreaterThanEqual => Self::compare(&left, &right),
+            _ => return Self::error(format!("unknown operator: {} {} {}", left, infix, right)),
+        };
+
+        match (infix, ordering) {
+            (Infix::LessThan, Some(ordering)) => Object::Bool(ordering == Ordering::Less),
+            (Infix::LessThanEqual, Some(ordering)) => Object::Bool(ordering != Ordering::Greater),
+            (Infix::GreaterThan, Some(ordering)) => Object::Bool(ordering == Ordering::Greater),
+            (_, Some(ordering)) => Object::Bool(ordering != Ordering::Less),
+            // Some pair of elements could not be ordered.
+            (_, None) => Self::error(format!("type mismatch: {} {} {}", left, infix, right)),
+        }
+    }
+
     fn eval_literal(&mut self, literal: &Literal) -> Object {
         match literal {
             Literal::Int(value) => Object::Int(*value),
+            Literal::BigInt(value) => Object::from_big_int(value.clone()),
+            Literal::Float(value) => Object::Float(*value),
             Literal::Bool(value) => Object::Bool(*value),
-            Literal::String(value) => Object::String(value.clone()),
+            Literal::String(value) | Literal::RawString(value, _) => Object::String(value.clone()),
+            Literal::Char(value) => Object::Char(*value),
+            Literal::Null => Object::Null,
             Literal::Array(objects) => self.eval_array_literal(objects),
             Literal::Hash(pairs) => self.eval_hash_literal(pairs),
         }
     }
 
-    fn eval_array_literal(&mut self, objects: &Vec<Expr>) -> Object {
-        Object::Array(
-            objects
-                .iter()
-                .map(|e| self.eval_expr(&e.clone()).unwrap_or(Object::Null))
-                .collect::<Vec<_>>(),
-        )
-    }
+    fn eval_array_literal(&mut self, objects: &[Expr]) -> Object {
+        let mut array = Vec::with_capacity(objects.len());
 
-    fn eval_hash_literal(&mut self, pairs: &Vec<(Expr, Expr)>) -> Object {
-        let mut hash = HashMap::new();
+        for expr in objects {
+            let object = self.eval_expr(expr).unwrap_or(Object::Null);
+            if Self::is_error(&object) {
+                return object;
+            }
+            array.push(object);
+        }
 
-        for (key_expr, value_expr) in pairs {
-            let key = self.eval_expr(key_expr).unwrap_or(Object::Null);
+        Object::Array(array)
+    }
+
+    fn eval_interpolated_expr(&mut self, parts: &[StringPart]) -> Object {
+        let mut result = String::new();
+
+        for part in parts {
+            match part {
+                StringPart::Text(text) => result.push_str(text),
+                StringPart::Expr(expr) => {
+                    let value = self.eval_expr(expr).unwrap_or(Object::Null);
+                    if Self::is_error(&value) {
+                        return value;
+                    }
+                    result.push_str(&value.str_value());
+                }
+            }
+        }
+
+        Object::String(result)
+    }
+
+    fn eval_hash_literal(&mut self, pairs: &[(Expr, Expr)]) -> Object {
+        let mut hash = HashMap::new();
+
+        for (key_expr, value_expr) in pairs {
+            let key = self.eval_expr(key_expr).unwrap_or(Object::Null);
             if Self::is_error(&key) {
                 return key;
             }
@@ -330,10 +1081,7 @@ impl Evaluator {
         consequence: &BlockStmt,
         alternative: &Option<BlockStmt>,
     ) -> Option<Object> {
-        let cond = match self.eval_expr(cond) {
-            Some(cond) => cond,
-            None => return None,
-        };
+        let cond = self.eval_expr(cond)?;
 
         if Self::is_truthy(cond) {
             self.eval_block_stmt(consequence)
@@ -347,12 +1095,8 @@ impl Evaluator {
     fn eval_while_expr(&mut self, cond: &Expr, consequence: &BlockStmt) -> Option<Object> {
         let mut result: Option<Object> = None;
 
-        loop {
-            let cond_result = match self.eval_expr(cond) {
-                Some(cond) => cond,
-                None => break,
-            };
-            if !Self::is_truthy(cond_result.clone()) {
+        while let Some(cond_result) = self.eval_expr(cond) {
+            if !Self::is_truthy(cond_result) {
                 break;
             }
 
@@ -361,12 +1105,83 @@ impl Evaluator {
                 Some(Object::BreakStatement) => {
                     result = Some(Object::Null);
                     break;
-                },
+                }
                 Some(Object::ContinueStatement) => {
                     result = Some(Object::Null);
                     continue;
-                },
+                }
+                Some(Object::ReturnValue(value)) => return Some(Object::ReturnValue(value)),
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    /// Runs `body` once per element of `iterable`. With one variable it is
+    /// bound to each item (or key, for hashes); with two, to the index (or
+    /// key) and the item.
+    fn eval_for_expr(
+        &mut self,
+        vars: &[Ident],
+        iterable: &Expr,
+        body: &BlockStmt,
+    ) -> Option<Object> {
+        let iterable = self.eval_expr(iterable)?;
+        let by_key = matches!(iterable, Object::Hash(_));
+
+        let pairs: Box<dyn Iterator<Item = (Object, Object)>> = match iterable {
+            Object::Array(items) => Box::new(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, item)| (Object::Int(i as i64), item)),
+            ),
+            Object::String(s) => Box::new(
+                s.chars()
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, c)| (Object::Int(i as i64), Object::Char(c))),
+            ),
+            Object::Range(start, end, step) => Box::new(
+                Object::range_values(start, end, step)
+                    .enumerate()
+                    .map(|(i, v)| (Object::Int(i as i64), Object::Int(v))),
+            ),
+            Object::Hash(hash) => {
+                let mut pairs = hash.into_iter().collect::<Vec<_>>();
+                pairs.sort_by(|(a, _), (b, _)| Self::key_order(a, b));
+                Box::new(pairs.into_iter())
+            }
+            Object::Error(msg) => return Some(Object::Error(msg)),
+            o => return Some(Self::error(format!("cannot iterate over {}", o))),
+        };
+        let mut result = None;
+
+        for (key, item) in pairs {
+            match vars {
+                [Ident(name)] if by_key => self.env.borrow_mut().set(name.clone(), &key),
+                [Ident(name)] => self.env.borrow_mut().set(name.clone(), &item),
+                [Ident(key_name), Ident(item_name)] => {
+                    self.env.borrow_mut().set(key_name.clone(), &key);
+                    self.env.borrow_mut().set(item_name.clone(), &item);
+                }
+                _ => {}
+            }
+
+            result = self.eval_block_stmt_with_continue_and_break_statement(body);
+            match result {
+                Some(Object::BreakStatement) => {
+                    result = Some(Object::Null);
+                    break;
+                }
+                Some(Object::ContinueStatement) => {
+                    result = Some(Object::Null);
+                    continue;
+                }
                 Some(Object::ReturnValue(value)) => return Some(Object::ReturnValue(value)),
+                Some(Object::Error(msg)) => return Some(Object::Error(msg)),
                 _ => {}
             }
         }
@@ -374,17 +1189,42 @@ impl Evaluator {
         result
     }
 
-    fn eval_call_expr(&mut self, func: &Box<Expr>, args: &Vec<Expr>) -> Object {
+    /// Orders hash keys for iteration: integers (big or not), then strings,
+    /// then chars, then `false`, then `true`.
+    fn key_order(a: &Object, b: &Object) -> Ordering {
+        let rank = |o: &Object| match o {
+            Object::Int(_) | Object::BigInt(_) => 0,
+            Object::String(_) => 1,
+            Object::Char(_) => 2,
+            Object::Bool(false) => 3,
+            _ => 4,
+        };
+
+        rank(a)
+            .cmp(&rank(b))
+            .then_with(|| Self::compare(a, b).unwrap_or(Ordering::Equal))
+    }
+
+    fn eval_call_expr(&mut self, func: &Expr, args: &[Expr]) -> Object {
         let args = args
             .iter()
             .map(|e| self.eval_expr(e).unwrap_or(Object::Null))
             .collect::<Vec<_>>();
 
-        let (params, body, env) = match self.eval_expr(&*func) {
-            Some(Object::Func(params, body, env)) => (params, body, env),
-            Some(Object::Builtin(expect_param_num, f)) => {
+        match self.eval_expr(func) {
+            Some(func) => self.call(func, args),
+            None => Object::Null,
+        }
+    }
+
+    /// Calls a pua function or a builtin with `args`. This is how builtins
+    /// such as `map` run the functions they are given.
+    pub fn call(&mut self, func: Object, args: Vec<Object>) -> Object {
+        let (params, body, env) = match func {
+            Object::Func(params, body, env) => (params, body, env),
+            Object::Builtin(expect_param_num, f) => {
                 if expect_param_num < 0 || expect_param_num == args.len() as i32 {
-                    return f(args);
+                    return f(self, args);
                 } else {
                     return Self::error(format!(
                         "wrong number of arguments. got={}, want={}",
@@ -393,8 +1233,7 @@ impl Evaluator {
                     ));
                 }
             }
-            Some(o) => return Self::error(format!("{} is not valid function", o)),
-            None => return Object::Null,
+            o => return Self::error(format!("{} is not valid function", o)),
         };
 
         if params.len() != args.len() {
@@ -408,7 +1247,7 @@ impl Evaluator {
         let current_env = Rc::clone(&self.env);
         let mut scoped_env = Env::new_with_outer(Rc::clone(&env));
         let list = params.iter().zip(args.iter());
-        for (_, (ident, o)) in list.enumerate() {
+        for (ident, o) in list {
             let Ident(name) = ident.clone();
             scoped_env.set(name, o);
         }
@@ -433,6 +1272,7 @@ mod tests {
     use evaluator::*;
     use lexer::Lexer;
     use parser::Parser;
+    use span::Pos;
 
     fn eval(input: &str) -> Option<Object> {
         Evaluator::new(Rc::new(RefCell::new(Env::from(new_builtins()))))
@@ -468,6 +1308,149 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_division_by_zero_and_overflow() {
+        let min_plus_one = &BigInt::from(i64::MAX) + &BigInt::from(1);
+        let tests = vec![
+            (
+                "1 / 0",
+                Object::Error(String::from("division by zero: 1 / 0")),
+            ),
+            (
+                "1 % 0",
+                Object::Error(String::from("division by zero: 1 % 0")),
+            ),
+            (
+                "(-9223372036854775807 - 1) / -1",
+                Object::BigInt(min_plus_one.clone()),
+            ),
+            ("(-9223372036854775807 - 1) % -1", Object::Int(0)),
+            ("-(-9223372036854775807 - 1)", Object::BigInt(min_plus_one)),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input));
+        }
+    }
+
+    #[test]
+    fn test_big_int_expr() {
+        let big = |s: &str| Some(Object::BigInt(s.parse().unwrap()));
+        let tests = vec![
+            ("99999999999999999999", big("99999999999999999999")),
+            ("-9223372036854775808", Some(Object::Int(i64::MIN))),
+            ("9223372036854775807 + 1", big("9223372036854775808")),
+            ("-9223372036854775807 - 2", big("-9223372036854775809")),
+            ("4611686018427387904 * 2", big("9223372036854775808")),
+            (
+                "(-9223372036854775807 - 1) / -1",
+                big("9223372036854775808"),
+            ),
+            ("-(-9223372036854775807 - 1)", big("9223372036854775808")),
+            ("9223372036854775808 - 1", Some(Object::Int(i64::MAX))),
+            (
+                "99999999999999999999 / 100000",
+                Some(Object::Int(999999999999999)),
+            ),
+            ("99999999999999999999 > 1", Some(Object::Bool(true))),
+            (
+                "99999999999999999999 == 99999999999999999999",
+                Some(Object::Bool(true)),
+            ),
+            ("99999999999999999999 * 0.5", Some(Object::Float(5e19))),
+            (
+                "let f = fn(n) { if (n < 2) { 1 } else { n * f(n - 1) } }; f(25)",
+                big("15511210043330985984000000"),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_arith_ops() {
+        let big = |s: &str| Some(Object::BigInt(s.parse().unwrap()));
+        let tests = vec![
+            ("7 % 3", Some(Object::Int(1))),
+            ("-7 % 3", Some(Object::Int(-1))),
+            ("7.5 % 2", Some(Object::Float(1.5))),
+            ("2 ** 10", Some(Object::Int(1024))),
+            ("2 ** 3 ** 2", Some(Object::Int(512))),
+            ("-2 ** 2", Some(Object::Int(-4))),
+            ("2 ** -1", Some(Object::Float(0.5))),
+            ("2.0 ** 0.5 > 1.41", Some(Object::Bool(true))),
+            ("2 ** 64", big("18446744073709551616")),
+            ("12 & 10", Some(Object::Int(8))),
+            ("12 | 10", Some(Object::Int(14))),
+            ("12 ^ 10", Some(Object::Int(6))),
+            ("~5", Some(Object::Int(-6))),
+            ("1 << 4", Some(Object::Int(16))),
+            ("-16 >> 2", Some(Object::Int(-4))),
+            ("1 >> 100", Some(Object::Int(0))),
+            ("1 << 64", big("18446744073709551616")),
+            ("(1 << 64) >> 63", Some(Object::Int(2))),
+            ("~(1 << 64)", big("-18446744073709551617")),
+            ("(2 ** 64 + 5) % 2 ** 64", Some(Object::Int(5))),
+            ("1 | 2 == 3", Some(Object::Bool(true))),
+            ("10 杠杆 2 余量 7", Some(Object::Int(2))),
+            (
+                "1 % 0",
+                Some(Object::Error(String::from("division by zero: 1 % 0"))),
+            ),
+            (
+                "1 << -1",
+                Some(Object::Error(String::from(
+                    "negative shift amount: 1 << -1",
+                ))),
+            ),
+            (
+                "(1 << 64) | 1",
+                Some(Object::Error(String::from(
+                    "bitwise operators need i64 operands: 18446744073709551616 | 1",
+                ))),
+            ),
+            (
+                "1.5 & 1.0",
+                Some(Object::Error(String::from("unknown operator: 1.5 & 1.0"))),
+            ),
+            (
+                "~true",
+                Some(Object::Error(String::from("unknown operator: ~true"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_float_expr() {
+        let tests = vec![
+            ("3.75", Some(Object::Float(3.75))),
+            ("-0.5", Some(Object::Float(-0.5))),
+            ("1.5 + 1.5", Some(Object::Float(3.0))),
+            ("1 + 0.5", Some(Object::Float(1.5))),
+            ("0.5 * 4", Some(Object::Float(2.0))),
+            ("7 / 2.0", Some(Object::Float(3.5))),
+            ("1 / 0.0", Some(Object::Float(f64::INFINITY))),
+            ("1 < 1.5", Some(Object::Bool(true))),
+            ("2.0 == 2", Some(Object::Bool(true))),
+            ("2.5 != 2.5", Some(Object::Bool(false))),
+            ("if (3.25) { 1 } else { 2 }", Some(Object::Int(2))),
+            (
+                "1.5 + true",
+                Some(Object::Error(String::from("type mismatch: 1.5 + true"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
     #[test]
     fn test_string_expr() {
         let input = "\"Hello World!\"";
@@ -488,6 +1471,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_char_raw_string_and_null() {
+        let tests = vec![
+            ("'抓'", Some(Object::Char('抓'))),
+            ("r\"\\n\"", Some(Object::String(String::from("\\n")))),
+            ("r#\"\"\"# == \"\\\"\"", Some(Object::Bool(true))),
+            ("null", Some(Object::Null)),
+            ("留白 == null", Some(Object::Bool(true))),
+            ("'a' == 'a'", Some(Object::Bool(true))),
+            ("'a' == \"a\"", Some(Object::Bool(false))),
+            ("'a' < 'b'", Some(Object::Bool(true))),
+            ("\"ab\" + 'c'", Some(Object::String(String::from("abc")))),
+            ("'a' + \"bc\"", Some(Object::String(String::from("abc")))),
+            ("'工' in \"打工人\"", Some(Object::Bool(true))),
+            ("{'a': 1}['a']", Some(Object::Int(1))),
+            ("str('a')", Some(Object::String(String::from("a")))),
+            (
+                "'a' + 'b'",
+                Some(Object::Error(String::from("unknown operator: 'a' + 'b'"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_interpolated_string() {
+        let tests = vec![
+            (
+                "let score = 375; f\"得分: {score}\"",
+                Some(Object::String(String::from("得分: 375"))),
+            ),
+            (
+                "let name = \"Jimmy\"; f\"{name}, {'p'}{6 + 1}, {[1, \"a\"]}\"",
+                Some(Object::String(String::from("Jimmy, p7, [1, \"a\"]"))),
+            ),
+            (
+                "f\"{{ {true} }}\"",
+                Some(Object::String(String::from("{ true }"))),
+            ),
+            (
+                "f\"{1 + true}\"",
+                Some(Object::Error(String::from("type mismatch: 1 + true"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
     #[test]
     fn test_boolean_expr() {
         let tests = vec![
@@ -514,37 +1550,398 @@ mod tests {
     }
 
     #[test]
-    fn test_array_literal() {
-        let input = "[1, 2 * 2, 3 + 3]";
+    fn test_equality() {
+        let tests = vec![
+            ("true == true", true),
+            ("三七五 != 三二五", true),
+            ("\"a\" == \"a\"", true),
+            ("\"a\" == 1", false),
+            ("1 == 1.0", true),
+            ("99999999999999999999 == 99999999999999999999.0", true),
+            ("[1, [2, \"x\"]] == [1, [2, \"x\"]]", true),
+            ("[1, 2] == [1, 2, 3]", false),
+            ("[1, 2] != [2, 1]", true),
+            ("{\"a\": [1], 2: true} == {2: true, \"a\": [1]}", true),
+            ("{\"a\": 1} == {\"a\": 2}", false),
+            ("first([]) == first([])", true),
+            ("first([]) == 0", false),
+            ("range(3) == range(0, 3)", true),
+            ("let f = fn(x) { x }; f == f", true),
+            ("fn(x) { x } == fn(x) { x }", true),
+            ("let g = fn() { fn(x) { x } }; g() == g()", false),
+            ("len == len", true),
+            ("len == first", false),
+            ("0.0 / 0.0 == 0.0 / 0.0", false),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(Object::Bool(expect)), eval(input), "{}", input);
+        }
+
+        // Errors in either operand are not compared but passed on.
+        for input in &[
+            "1 / 0 == 1",
+            "1 / 0 != 1",
+            "(1 / 0) == (1 / 0)",
+            "1 == 1 / 0",
+            "1 in [1 / 0]",
+            "1 / 0 in [1]",
+        ] {
+            assert_eq!(
+                Some(Object::Error(String::from("division by zero: 1 / 0"))),
+                eval(input),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let tests = vec![
+            ("\"apple\" < \"banana\"", Some(Object::Bool(true))),
+            ("\"b\" >= \"abc\"", Some(Object::Bool(true))),
+            ("\"打工人\" > \"打工\"", Some(Object::Bool(true))),
+            ("[1, 2] < [1, 3]", Some(Object::Bool(true))),
+            ("[1, 2] < [1, 2, 0]", Some(Object::Bool(true))),
+            ("[2] <= [1, 5]", Some(Object::Bool(false))),
+            ("[[1, \"b\"]] > [[1, \"a\"]]", Some(Object::Bool(true))),
+            ("[1.5] < [2]", Some(Object::Bool(true))),
+            ("99999999999999999999 > 1.5", Some(Object::Bool(true))),
+            (
+                "1 < \"a\"",
+                Some(Object::Error(String::from("type mismatch: 1 < \"a\""))),
+            ),
+            (
+                "[1] < [\"a\"]",
+                Some(Object::Error(String::from("type mismatch: [1] < [\"a\"]"))),
+            ),
+            (
+                "[1] < 1",
+                Some(Object::Error(String::from("type mismatch: [1] < 1"))),
+            ),
+            (
+                "true < false",
+                Some(Object::Error(String::from(
+                    "unknown operator: true < false",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_logical_expr() {
+        let tests =
+            vec![
+            ("true && true", Some(Object::Bool(true))),
+            ("true && false", Some(Object::Bool(false))),
+            ("false || true", Some(Object::Bool(true))),
+            ("false || false", Some(Object::Bool(false))),
+            ("1 < 2 && 2 < 3", Some(Object::Bool(true))),
+            ("三二五 兜底 三七五 协同 三七五", Some(Object::Bool(true))),
+            ("0 || \"default\"", Some(Object::Int(0))),
+            ("325 || \"default\"", Some(Object::String(String::from("default")))),
+            ("false && undefined_name", Some(Object::Bool(false))),
+            ("true || undefined_name", Some(Object::Bool(true))),
+            (
+                "let n = 0; let bump = fn() { n += 1; true }; false && bump(); true || bump(); n",
+                Some(Object::Int(0)),
+            ),
+            (
+                "true && undefined_name",
+                Some(Object::Error(String::from("identifier not found: undefined_name"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_sequence_ops() {
+        let tests = vec![
+            (
+                "[1, 2] + [3]",
+                Some(Object::Array(vec![
+                    Object::Int(1),
+                    Object::Int(2),
+                    Object::Int(3),
+                ])),
+            ),
+            (
+                "let a = [1]; a += [2]; a",
+                Some(Object::Array(vec![Object::Int(1), Object::Int(2)])),
+            ),
+            ("[0] * 3", Some(Object::Array(vec![Object::Int(0); 3]))),
+            (
+                "2 放大 [\"a\"]",
+                Some(Object::Array(vec![Object::String(String::from("a")); 2])),
+            ),
+            ("[1] * -1", Some(Object::Array(vec![]))),
+            ("\"ab\" * 3", Some(Object::String(String::from("ababab")))),
+            ("[] * 9223372036854775807", Some(Object::Array(vec![]))),
+            (
+                "\"a\" * 9223372036854775807",
+                Some(Object::Error(String::from(
+                    "repetition too large: \"a\" * 9223372036854775807",
+                ))),
+            ),
+            (
+                "[1] * 9223372036854775807",
+                Some(Object::Error(String::from(
+                    "repetition too large: [1] * 9223372036854775807",
+                ))),
+            ),
+            ("3 * \"六\"", Some(Object::String(String::from("六六六")))),
+            ("2 in [1, 2]", Some(Object::Bool(true))),
+            ("[2] in [1, [2]]", Some(Object::Bool(true))),
+            ("2.0 沉淀 [1, 2]", Some(Object::Bool(true))),
+            ("3 in [1, 2]", Some(Object::Bool(false))),
+            ("\"p7\" in {\"p7\": 1}", Some(Object::Bool(true))),
+            ("1 in {\"1\": 1}", Some(Object::Bool(false))),
+            ("\"工\" in \"打工人\"", Some(Object::Bool(true))),
+            ("\"\" in \"\"", Some(Object::Bool(true))),
+            ("4 in range(0, 10, 2)", Some(Object::Bool(true))),
+            ("5 in range(0, 10, 2)", Some(Object::Bool(false))),
+            ("7 in range(10, 0, -3)", Some(Object::Bool(true))),
+            ("!(3 in [1, 2]) && 1 + 1 in [2]", Some(Object::Bool(true))),
+            (
+                "[1] * [2]",
+                Some(Object::Error(String::from("unknown operator: [1] * [2]"))),
+            ),
+            (
+                "1 in \"123\"",
+                Some(Object::Error(String::from("type mismatch: 1 in \"123\""))),
+            ),
+            (
+                "[1] in {}",
+                Some(Object::Error(String::from("unusable as hash key: [1]"))),
+            ),
+            (
+                "1 in 1",
+                Some(Object::Error(String::from("unknown operator: 1 in 1"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_array_literal() {
+        let input = "[1, 2 * 2, 3 + 3]";
+
+        assert_eq!(
+            Some(Object::Array(vec![
+                Object::Int(1),
+                Object::Int(4),
+                Object::Int(6),
+            ])),
+            eval(input),
+        );
+    }
+
+    #[test]
+    fn test_array_index_expr() {
+        let tests = vec![
+            ("[1, 2, 3][0]", Some(Object::Int(1))),
+            ("[1, 2, 3][1]", Some(Object::Int(2))),
+            ("let i = 0; [1][i]", Some(Object::Int(1))),
+            ("[1, 2, 3][1 + 1];", Some(Object::Int(3))),
+            ("let myArray = [1, 2, 3]; myArray[2];", Some(Object::Int(3))),
+            (
+                "let myArray = [1, 2, 3]; myArray[0] + myArray[1] + myArray[2];",
+                Some(Object::Int(6)),
+            ),
+            (
+                "let myArray = [1, 2, 3]; let i = myArray[0]; myArray[i];",
+                Some(Object::Int(2)),
+            ),
+            ("[1, 2, 3][3]", Some(Object::Null)),
+            ("[1, 2, 3][-1]", Some(Object::Int(3))),
+            ("[1, 2, 3][-3]", Some(Object::Int(1))),
+            ("[1, 2, 3][-4]", Some(Object::Null)),
+            ("[][0]", Some(Object::Null)),
+            (
+                "let a = [1, 2, 3]; a[-1] = 4; a",
+                Some(Object::Array(vec![
+                    Object::Int(1),
+                    Object::Int(2),
+                    Object::Int(4),
+                ])),
+            ),
+            ("\"抓手\"[-1]", Some(Object::Char('手'))),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_string_index_expr() {
+        let tests = vec![
+            ("\"抓手\"[0]", Some(Object::Char('抓'))),
+            ("\"抓手\"[1]", Some(Object::Char('手'))),
+            ("\"抓手\"[2]", Some(Object::Null)),
+            (
+                "\"年年有抓手\"[range(2, 5)]",
+                Some(Object::String(String::from("有抓手"))),
+            ),
+            (
+                "\"abcdef\"[range(-3, 100, 2)]",
+                Some(Object::String(String::from("bdf"))),
+            ),
+            (
+                "\"abcdef\"[range(100, -100, -2)]",
+                Some(Object::String(String::from("eca"))),
+            ),
+            ("\"abc\"[range(3, 1)]", Some(Object::String(String::new()))),
+            (
+                "\"abc\"[true]",
+                Some(Object::Error(String::from(
+                    "index operator not supported: \"abc\"",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_slice_expr() {
+        let ints = |items: &[i64]| {
+            Some(Object::Array(
+                items.iter().map(|&i| Object::Int(i)).collect(),
+            ))
+        };
+        let string = |s: &str| Some(Object::String(String::from(s)));
+
+        let tests = vec![
+            ("[1, 2, 3, 4, 5][1:3]", ints(&[2, 3])),
+            ("[1, 2, 3, 4, 5][:2]", ints(&[1, 2])),
+            ("[1, 2, 3, 4, 5][3:]", ints(&[4, 5])),
+            ("[1, 2, 3, 4, 5][:]", ints(&[1, 2, 3, 4, 5])),
+            ("[1, 2, 3, 4, 5][-2:]", ints(&[4, 5])),
+            ("[1, 2, 3, 4, 5][::2]", ints(&[1, 3, 5])),
+            ("[1, 2, 3, 4, 5][::-1]", ints(&[5, 4, 3, 2, 1])),
+            ("[1, 2, 3, 4, 5][3:0:-2]", ints(&[4, 2])),
+            ("[1, 2, 3, 4, 5][-100:100]", ints(&[1, 2, 3, 4, 5])),
+            ("[1, 2, 3, 4, 5][4:1]", ints(&[])),
+            ("[1, 2, 3][99999999999999999999:]", ints(&[])),
+            ("[1, 2, 3][null:2]", ints(&[1, 2])),
+            ("\"年年有抓手\"[3:]", string("抓手")),
+            ("\"年年有抓手\"[::-1]", string("手抓有年年")),
+            (
+                "[1, 2][::0]",
+                Some(Object::Error(String::from("slice step cannot be zero"))),
+            ),
+            (
+                "[1, 2][\"a\":]",
+                Some(Object::Error(String::from(
+                    "slice index must be int. got \"a\"",
+                ))),
+            ),
+            (
+                "true[1:]",
+                Some(Object::Error(String::from(
+                    "slice operator not supported: true",
+                ))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_error() {
+        let tests = vec![
+            (
+                "[1, 2, 3][3]",
+                Object::Error(String::from("index out of range: 3 (length 3)")),
+            ),
+            (
+                "[1, 2, 3][-4]",
+                Object::Error(String::from("index out of range: -4 (length 3)")),
+            ),
+            (
+                "\"抓手\"[2]",
+                Object::Error(String::from("index out of range: 2 (length 2)")),
+            ),
+            ("[1, 2, 3][2]", Object::Int(3)),
+            ("[1, 2, 3][5:]", Object::Array(vec![])),
+        ];
 
-        assert_eq!(
-            Some(Object::Array(vec![
-                Object::Int(1),
-                Object::Int(4),
-                Object::Int(6),
-            ])),
-            eval(input),
-        );
+        for (input, expect) in tests {
+            let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Env::from(new_builtins()))));
+            evaluator.set_out_of_range(OutOfRange::Error);
+            let program = Parser::new(Lexer::new(input)).parse();
+            assert_eq!(Some(expect), evaluator.eval(&program));
+        }
     }
 
     #[test]
-    fn test_array_index_expr() {
+    fn test_string_builtins() {
+        let strings = |items: &[&str]| {
+            Some(Object::Array(
+                items
+                    .iter()
+                    .map(|s| Object::String(s.to_string()))
+                    .collect(),
+            ))
+        };
+        let string = |s: &str| Some(Object::String(String::from(s)));
+
         let tests = vec![
-            ("[1, 2, 3][0]", Some(Object::Int(1))),
-            ("[1, 2, 3][1]", Some(Object::Int(2))),
-            ("let i = 0; [1][i]", Some(Object::Int(1))),
-            ("[1, 2, 3][1 + 1];", Some(Object::Int(3))),
-            ("let myArray = [1, 2, 3]; myArray[2];", Some(Object::Int(3))),
+            ("split(\"a,b,,c\", \",\")", strings(&["a", "b", "", "c"])),
+            ("拆解(\" 降本  增效 \")", strings(&["降本", "增效"])),
+            ("split(\"抓手\", \"\")", strings(&["抓", "手"])),
+            ("join([\"a\", 1, 'c'], \"-\")", string("a-1-c")),
+            ("整合([\"降本\", \"增效\"])", string("降本增效")),
+            ("trim(\"  赋能\\n\")", string("赋能")),
+            ("upper(\"abc\")", string("ABC")),
+            ("下沉(\"ÀB\")", string("àb")),
+            ("replace(\"a-b-c\", \"-\", '+')", string("a+b+c")),
+            ("contains(\"打工人\", \"工\")", Some(Object::Bool(true))),
+            ("涵盖(\"打工人\", '鱼')", Some(Object::Bool(false))),
+            ("starts_with(\"抓手\", \"抓\")", Some(Object::Bool(true))),
+            ("收尾(\"抓手\", \"抓\")", Some(Object::Bool(false))),
+            ("find(\"年年有抓手\", \"抓手\")", Some(Object::Int(3))),
+            ("定位(\"年年有抓手\", \"闭环\")", Some(Object::Null)),
             (
-                "let myArray = [1, 2, 3]; myArray[0] + myArray[1] + myArray[2];",
-                Some(Object::Int(6)),
+                "chars(\"抓手\")",
+                Some(Object::Array(vec![Object::Char('抓'), Object::Char('手')])),
             ),
+            ("repeat(\"六\", 3)", string("六六六")),
+            ("复制('六', -1)", string("")),
             (
-                "let myArray = [1, 2, 3]; let i = myArray[0]; myArray[i];",
-                Some(Object::Int(2)),
+                "repeat(\"a\", 9223372036854775807)",
+                Some(Object::Error(String::from(
+                    "repetition too large: \"a\" * 9223372036854775807",
+                ))),
+            ),
+            (
+                "trim(1)",
+                Some(Object::Error(String::from(
+                    "argument to `trim` must be string. got 1",
+                ))),
+            ),
+            (
+                "contains(\"abc\", 1)",
+                Some(Object::Error(String::from(
+                    "argument to `contains` must be string. got 1",
+                ))),
             ),
-            ("[1, 2, 3][3]", Some(Object::Null)),
-            ("[1, 2, 3][-1]", Some(Object::Null)),
         ];
 
         for (input, expect) in tests {
@@ -552,6 +1949,149 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_higher_order_builtins() {
+        let ints = |items: &[i64]| Object::Array(items.iter().map(|&i| Object::Int(i)).collect());
+        let strings = |items: &[&str]| {
+            Object::Array(
+                items
+                    .iter()
+                    .map(|s| Object::String(s.to_string()))
+                    .collect(),
+            )
+        };
+
+        let tests = vec![
+            ("map([1, 2, 3], fn(x) { x * 2 })", ints(&[2, 4, 6])),
+            ("转化(range(3), fn(x) { x + 1 })", ints(&[1, 2, 3])),
+            ("map([\"1\", \"2\"], int)", ints(&[1, 2])),
+            ("let k = 10; map([1], fn(x) { x + k })", ints(&[11])),
+            ("filter([1, 2, 3, 4], fn(x) { x % 2 == 0 })", ints(&[2, 4])),
+            (
+                "优化([\"p6\", \"p7\", \"p8\"], fn(x) { x != \"p6\" })",
+                strings(&["p7", "p8"]),
+            ),
+            ("reduce([1, 2, 3], fn(a, b) { a + b })", Object::Int(6)),
+            ("收敛([1, 2, 3], fn(a, b) { a * b }, 10)", Object::Int(60)),
+            ("reduce([], fn(a, b) { a + b }, 0)", Object::Int(0)),
+            ("sort([3, 1, 2])", ints(&[1, 2, 3])),
+            ("排名([3, 1, 2], fn(a, b) { b - a })", ints(&[3, 2, 1])),
+            (
+                "sort([\"b\", \"a\", \"c\"], fn(a, b) { 0 })",
+                strings(&["b", "a", "c"]),
+            ),
+            (
+                "sort_by([\"ccc\", \"a\", \"bb\"], len)",
+                strings(&["a", "bb", "ccc"]),
+            ),
+            (
+                "赛马([[1, \"b\"], [0, \"a\"], [1, \"a\"]], fn(p) { p[0] })",
+                Object::Array(vec![
+                    Object::Array(vec![Object::Int(0), Object::String(String::from("a"))]),
+                    Object::Array(vec![Object::Int(1), Object::String(String::from("b"))]),
+                    Object::Array(vec![Object::Int(1), Object::String(String::from("a"))]),
+                ]),
+            ),
+            ("any([1, 2, 3], fn(x) { x > 2 })", Object::Bool(true)),
+            ("亮点([325, 325])", Object::Bool(false)),
+            ("all([1, 2, 3], fn(x) { x > 0 })", Object::Bool(true)),
+            ("全链路([])", Object::Bool(true)),
+            ("any([1, 2], fn(x) { x > 0 || 1 / 0 })", Object::Bool(true)),
+            (
+                "any(range(1000000000000), fn(x) { x > 3 })",
+                Object::Bool(true),
+            ),
+            (
+                "all(range(1000000000000), fn(x) { x < 3 })",
+                Object::Bool(false),
+            ),
+            (
+                "zip([1, 2, 3], \"ab\")",
+                Object::Array(vec![
+                    Object::Array(vec![Object::Int(1), Object::Char('a')]),
+                    Object::Array(vec![Object::Int(2), Object::Char('b')]),
+                ]),
+            ),
+            (
+                "zip([1, 2], range(9223372036854775807))",
+                Object::Array(vec![ints(&[1, 0]), ints(&[2, 1])]),
+            ),
+            (
+                "map(range(1000000000000), fn(x) { 1 / 0 })",
+                Object::Error(String::from("division by zero: 1 / 0")),
+            ),
+            (
+                "reduce(range(1000000000000), fn(acc, x) { if (x > 3) { 1 / 0 } else { acc + x } })",
+                Object::Error(String::from("division by zero: 1 / 0")),
+            ),
+            (
+                "sort(range(1000000000000))",
+                Object::Error(String::from(
+                    "range too large for `sort`: range(0, 1000000000000)",
+                )),
+            ),
+            (
+                "reverse(range(0, 1000000000000, 2))",
+                Object::Error(String::from(
+                    "range too large for `reverse`: range(0, 1000000000000, 2)",
+                )),
+            ),
+            (
+                "盘点([\"a\"])",
+                Object::Array(vec![Object::Array(vec![
+                    Object::Int(0),
+                    Object::String(String::from("a")),
+                ])]),
+            ),
+            ("reverse([1, 2, 3])", ints(&[3, 2, 1])),
+            ("复盘(\"抓手\")", Object::String(String::from("手抓"))),
+            (
+                "flatten([1, [2, [3]], []])",
+                Object::Array(vec![Object::Int(1), Object::Int(2), ints(&[3])]),
+            ),
+            ("扁平化([1, [2, [3]]], 5)", ints(&[1, 2, 3])),
+            ("unique([1, 2, 1, 3, 2])", ints(&[1, 2, 3])),
+            (
+                "差异化([1, 1.0, [1], [1]])",
+                Object::Array(vec![Object::Int(1), ints(&[1])]),
+            ),
+            (
+                "map([1, 0], fn(x) { 1 / x })",
+                Object::Error(String::from("division by zero: 1 / 0")),
+            ),
+            (
+                "map([1], fn(a, b) { a })",
+                Object::Error(String::from(
+                    "wrong number of arguments: 2 expected but 1 given",
+                )),
+            ),
+            (
+                "sort([1, \"a\"])",
+                Object::Error(String::from("type mismatch: \"a\" < 1")),
+            ),
+            (
+                "sort([1, 2], fn(a, b) { true })",
+                Object::Error(String::from(
+                    "comparator of `sort` must return a number. got true",
+                )),
+            ),
+            (
+                "reduce([], fn(a, b) { a })",
+                Object::Error(String::from("`reduce` of nothing with no initial value")),
+            ),
+            (
+                "filter(1, fn(x) { x })",
+                Object::Error(String::from(
+                    "argument to `filter` must be array, range or string. got 1",
+                )),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(Some(expect), eval(input), "{}", input);
+        }
+    }
+
     #[test]
     fn test_hash_literal() {
         let input = r#"
@@ -625,6 +2165,15 @@ let two = "two";
             ("if (1 >= 2) { 10 }", None),
             ("if (1 >= 2) { 10 } else { 20 }", Some(Object::Int(20))),
             ("if (1 <= 2) { 10 } else { 20 }", Some(Object::Int(10))),
+            (
+                "if (1 > 2) { 10 } else if (2 > 1) { 20 } else { 30 }",
+                Some(Object::Int(20)),
+            ),
+            (
+                "细分 (三二五) { 10 } 路径 细分 (三二五) { 20 } 路径 { 30 }",
+                Some(Object::Int(30)),
+            ),
+            ("if (false) { 10 } else if (false) { 20 }", None),
         ];
 
         for (input, expect) in tests {
@@ -671,6 +2220,78 @@ let two = "two";
         }
     }
 
+    #[test]
+    fn test_for_expr() {
+        let tests = vec![
+            (
+                "let sum = 0; for (x in [1, 2, 3]) { sum += x; } sum",
+                Some(Object::Int(6)),
+            ),
+            (
+                "let sum = 0; for (i, x in [5, 6, 7]) { sum += i * x; } sum",
+                Some(Object::Int(20)),
+            ),
+            (
+                "let s = \"\"; for (c in \"抓手\") { s = c + s; } s",
+                Some(Object::String(String::from("手抓"))),
+            ),
+            (
+                "let a = []; for (c in \"抓手\") { a = push(a, c); } a",
+                Some(Object::Array(vec![Object::Char('抓'), Object::Char('手')])),
+            ),
+            (
+                "let a = []; for (c in \"ab\") { a = push(a, c == \"ab\"[0]); } a",
+                Some(Object::Array(vec![Object::Bool(true), Object::Bool(false)])),
+            ),
+            (
+                "let s = \"\"; for (k, v in {\"b\": 2, \"a\": 1}) { s += k + str(v); } s",
+                Some(Object::String(String::from("a1b2"))),
+            ),
+            (
+                "let keys = []; for (k in {2: 0, 1: 0}) { keys = push(keys, k); } keys",
+                Some(Object::Array(vec![Object::Int(1), Object::Int(2)])),
+            ),
+            (
+                "let sum = 0; 拉通 (x 沉淀 排期(10)) { 细分 (x == 3) { continue; } 细分 (x == 5) { 破圈; } sum += x; } sum",
+                Some(Object::Int(7)),
+            ),
+            (
+                "let a = []; for (x in range(10, 0, -3)) { a = push(a, x); } a",
+                Some(Object::Array(vec![
+                    Object::Int(10),
+                    Object::Int(7),
+                    Object::Int(4),
+                    Object::Int(1),
+                ])),
+            ),
+            (
+                "let f = fn() { for (x in range(1, 100)) { if (x * x > 50) { return x; } } }; f()",
+                Some(Object::Int(8)),
+            ),
+            ("len(range(0, 10, 3))", Some(Object::Int(4))),
+            ("len(range(10, 0, -3))", Some(Object::Int(4))),
+            ("len(range(5, 0))", Some(Object::Int(0))),
+            ("len(range(0, 1000000000000))", Some(Object::Int(1000000000000))),
+            (
+                "len(range(-9223372036854775807 - 1, 9223372036854775807))",
+                Some(Object::BigInt("18446744073709551615".parse().unwrap())),
+            ),
+            ("range(2, 5)", Some(Object::Range(2, 5, 1))),
+            (
+                "for (x in 5) { x }",
+                Some(Object::Error(String::from("cannot iterate over 5"))),
+            ),
+            (
+                "range(1, 2, 0)",
+                Some(Object::Error(String::from("`range` step must not be zero"))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
     #[test]
     fn test_return_stmt() {
         let tests = vec![
@@ -713,6 +2334,88 @@ if (10 > 1) {
         }
     }
 
+    #[test]
+    fn test_assign_stmt() {
+        let tests = vec![
+            ("let a = 5; a = 6; a;", Some(Object::Int(6))),
+            (
+                "let a = 5; a += 2; a *= 3; a -= 1; a /= 4; a;",
+                Some(Object::Int(5)),
+            ),
+            (
+                "let a = 7; a %= 4; a **= 3; a <<= 2; a >>= 1; a |= 1; a &= 7; a ^= 2; a;",
+                Some(Object::Int(5)),
+            ),
+            (
+                "let s = \"a\"; s += \"b\"; s;",
+                Some(Object::String(String::from("ab"))),
+            ),
+            (
+                "let i = 0; let f = fn() { i = i + 1; }; f(); f(); i;",
+                Some(Object::Int(2)),
+            ),
+            (
+                "let i = 0; let sum = 0; while (i < 4) { i += 1; sum += i; } sum;",
+                Some(Object::Int(10)),
+            ),
+            (
+                "let a = [1, 2, 3]; a[1] = 9; a;",
+                Some(Object::Array(vec![
+                    Object::Int(1),
+                    Object::Int(9),
+                    Object::Int(3),
+                ])),
+            ),
+            (
+                "let a = [[1], [2]]; a[1][0] += 5; a[1];",
+                Some(Object::Array(vec![Object::Int(7)])),
+            ),
+            (
+                "let h = {\"n\": 1}; h.n += 1; h.m = 5; h.n + h[\"m\"];",
+                Some(Object::Int(7)),
+            ),
+            (
+                "let a = [1]; let b = a; a[0] = 2; b[0];",
+                Some(Object::Int(1)),
+            ),
+            (
+                "let n = 0; let next = fn() { n += 1; n - 1 }; let a = [0, 0, 0]; a[next()] += 5; [a, n];",
+                Some(Object::Array(vec![
+                    Object::Array(vec![Object::Int(5), Object::Int(0), Object::Int(0)]),
+                    Object::Int(1),
+                ])),
+            ),
+            (
+                "let n = 0; let next = fn() { n += 1; n - 1 }; let a = [[0, 0], [0, 0]]; a[next()][next()] += 5; [a, n];",
+                Some(Object::Array(vec![
+                    Object::Array(vec![
+                        Object::Array(vec![Object::Int(0), Object::Int(5)]),
+                        Object::Array(vec![Object::Int(0), Object::Int(0)]),
+                    ]),
+                    Object::Int(2),
+                ])),
+            ),
+            (
+                "b = 1;",
+                Some(Object::Error(String::from("identifier not found: b"))),
+            ),
+            (
+                "let a = [1]; a[1] = 2;",
+                Some(Object::Error(String::from(
+                    "index out of range: 1 (length 1)",
+                ))),
+            ),
+            (
+                "let a = 1; a += \"x\";",
+                Some(Object::Error(String::from("type mismatch: 1 + \"x\""))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
     #[test]
     fn test_blank_stmt() {
         let tests = vec![
@@ -748,11 +2451,11 @@ identity(100);
         assert_eq!(
             Some(Object::Func(
                 vec![Ident(String::from("x"))],
-                vec![Stmt::Expr(Expr::Infix(
+                vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Ident(Ident(String::from("x")))),
-                    Box::new(Expr::Literal(Literal::Int(2))),
-                ))],
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("x"))))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                ))))],
                 Rc::new(RefCell::new(Env::from(new_builtins()))),
             )),
             eval(input),
@@ -842,12 +2545,62 @@ addTwo(2);
                     "argument to `len` not supported, got 1",
                 ))),
             ),
+            ("len(\"抓手\")", Some(Object::Int(2))),
+            ("len(\"👍🏽\")", Some(Object::Int(2))),
+            ("len(\"👍🏽\", \"grapheme\")", Some(Object::Int(1))),
             (
                 "len(\"one\", \"two\")",
                 Some(Object::Error(String::from(
-                    "wrong number of arguments. got=2, want=1",
+                    "unknown mode for `len`: \"two\", want \"grapheme\"",
+                ))),
+            ),
+            // quit
+            (
+                "quit(256)",
+                Some(Object::Error(String::from(
+                    "exit status for `quit` must be between 0 and 255. got 256",
+                ))),
+            ),
+            (
+                "淘汰(-1)",
+                Some(Object::Error(String::from(
+                    "exit status for `quit` must be between 0 and 255. got -1",
+                ))),
+            ),
+            // int, float
+            ("int(3.75)", Some(Object::Int(3))),
+            (
+                "int(1e20)",
+                Some(Object::BigInt("100000000000000000000".parse().unwrap())),
+            ),
+            (
+                "atoi(\"-99999999999999999999\")",
+                Some(Object::BigInt("-99999999999999999999".parse().unwrap())),
+            ),
+            ("atoi(\"42\")", Some(Object::Int(42))),
+            (
+                "str(99999999999999999999 + 1)",
+                Some(Object::String(String::from("100000000000000000000"))),
+            ),
+            ("float(99999999999999999999 + 1)", Some(Object::Float(1e20))),
+            ("int(-3.75)", Some(Object::Int(-3))),
+            ("收口(\"42\")", Some(Object::Int(42))),
+            ("int(true)", Some(Object::Int(1))),
+            (
+                "int(\"3.75\")",
+                Some(Object::Error(String::from(
+                    "argument to `int` must be valid digits. got \"3.75\"",
+                ))),
+            ),
+            ("float(3)", Some(Object::Float(3.0))),
+            ("颗粒度(\"3.75\")", Some(Object::Float(3.75))),
+            (
+                "float([])",
+                Some(Object::Error(String::from(
+                    "argument to `float` not supported, got []",
                 ))),
             ),
+            ("str(2.0 * 1.5)", Some(Object::String(String::from("3.0")))),
             // first
             ("first([1, 2, 3])", Some(Object::Int(1))),
             ("first([])", Some(Object::Null)),
@@ -1012,6 +2765,20 @@ if (10 > 1) {
                 "foobar",
                 Some(Object::Error(String::from("identifier not found: foobar"))),
             ),
+            (
+                "let x = 0; 1 / x",
+                Some(Object::Error(String::from("division by zero: 1 / 0"))),
+            ),
+            (
+                "let x = 1; x /= 0; x",
+                Some(Object::Error(String::from("division by zero: 1 / 0"))),
+            ),
+            (
+                "99999999999999999999 / (5 - 5)",
+                Some(Object::Error(String::from(
+                    "division by zero: 99999999999999999999 / 0",
+                ))),
+            ),
             (
                 "{\"name\": \"Monkey\"}[fn(x) { x }]",
                 Some(Object::Error(String::from(
@@ -1025,6 +2792,66 @@ if (10 > 1) {
         }
     }
 
+    #[test]
+    fn test_comment() {
+        let tests = vec![
+            ("5; // five", Some(Object::Int(5))),
+            (
+                "let a = 2; /* a */ a * 划重点 ignored\n 3",
+                Some(Object::Int(6)),
+            ),
+            ("fn() { 1 // one\n }()", Some(Object::Int(1))),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, eval(input));
+        }
+    }
+
+    #[test]
+    fn test_error_span() {
+        let input = "let a = 1;\nlet b = len(a);";
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Env::from(new_builtins()))));
+        let result = evaluator.eval(&Parser::new(Lexer::new(input)).parse());
+
+        assert_eq!(
+            Some(Object::Error(String::from(
+                "argument to `len` not supported, got 1"
+            ))),
+            result
+        );
+        assert_eq!(
+            Some(Span::new(Pos::new(19, 2, 9), Pos::new(25, 2, 15))),
+            evaluator.error_span()
+        );
+
+        let input = "let a = 1;\na += \"x\";";
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Env::from(new_builtins()))));
+        let result = evaluator.eval(&Parser::new(Lexer::new(input)).parse());
+
+        assert_eq!(
+            Some(Object::Error(String::from("type mismatch: 1 + \"x\""))),
+            result
+        );
+        assert_eq!(
+            Some(Span::new(Pos::new(11, 2, 1), Pos::new(12, 2, 2))),
+            evaluator.error_span()
+        );
+    }
+
+    #[test]
+    fn test_eval_with() {
+        let input = "1 + 1; let a = 3; a * 2; if (true) { return 7; } 8";
+        let mut evaluator = Evaluator::new(Rc::new(RefCell::new(Env::from(new_builtins()))));
+        let mut values = vec![];
+        let result = evaluator.eval_with(&Parser::new(Lexer::new(input)).parse(), |_, value| {
+            values.push(value.clone())
+        });
+
+        assert_eq!(Some(Object::Int(7)), result);
+        assert_eq!(vec![Object::Int(2), Object::Int(6), Object::Int(7)], values);
+    }
+
     // FIXME Someday, I want to run Z Combinator...
     //     #[test]
     //     fn test_z_combinator() {
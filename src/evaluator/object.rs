@@ -1,7 +1,9 @@
-#![allow(clippy::derive_hash_xor_eq)]
+#![allow(clippy::derived_hash_with_manual_eq)]
 
 use ast::*;
+use bigint::BigInt;
 use evaluator::env::*;
+use evaluator::Evaluator;
 use lexer::unescape::escape_str;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -9,15 +11,29 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
-pub type BuiltinFunc = fn(Vec<Object>) -> Object;
+/// Builtins get the evaluator so that they can call back into pua
+/// functions with `Evaluator::call`.
+pub type BuiltinFunc = fn(&mut Evaluator, Vec<Object>) -> Object;
 
+/// The most a single operation builds at once: 256 MiB of string or that
+/// many array elements.
+pub const MAX_BUILD_LEN: usize = 1 << 28;
+
+#[allow(unpredictable_function_pointer_comparisons)]
 #[derive(PartialEq, Clone, Debug)]
 pub enum Object {
     Int(i64),
+    /// Only for values outside the range of `Int`; see `Object::from_big_int`.
+    BigInt(BigInt),
+    Float(f64),
     String(String),
+    Char(char),
     Bool(bool),
     Array(Vec<Object>),
     Hash(HashMap<Object, Object>),
+    /// `range(start, end, step)`: the integers from `start` up to, but not
+    /// including, `end`.
+    Range(i64, i64, i64),
     Func(Vec<Ident>, BlockStmt, Rc<RefCell<Env>>),
     Builtin(i32, BuiltinFunc),
     Null,
@@ -32,7 +48,11 @@ impl fmt::Display for Object {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Object::Int(ref value) => write!(f, "{}", value),
+            Object::BigInt(ref value) => write!(f, "{}", value),
+            // `{:?}` keeps the `.0` of whole numbers, so floats stay floats.
+            Object::Float(ref value) => write!(f, "{:?}", value),
             Object::String(ref value) => write!(f, "{}", escape_str(value)),
+            Object::Char(ref value) => write!(f, "{:?}", value),
             Object::Bool(ref value) => write!(f, "{}", value),
             Object::Array(ref objects) => {
                 let mut result = String::new();
@@ -67,6 +87,8 @@ impl fmt::Display for Object {
                 }
                 write!(f, "fn({}) {{ ... }}", result)
             }
+            Object::Range(start, end, 1) => write!(f, "range({}, {})", start, end),
+            Object::Range(start, end, step) => write!(f, "range({}, {}, {})", start, end, step),
             Object::Builtin(_, _) => write!(f, "[builtin function]"),
             Object::Null => write!(f, "null"),
             Object::BreakStatement => write!(f, "[break statement]"),
@@ -77,14 +99,79 @@ impl fmt::Display for Object {
     }
 }
 
+impl Object {
+    /// Wraps `value` as an `Int` whenever it fits, so every integer has a
+    /// single representation.
+    pub fn from_big_int(value: BigInt) -> Object {
+        match value.to_i64() {
+            Some(value) => Object::Int(value),
+            None => Object::BigInt(value),
+        }
+    }
+
+    /// What `str` turns the value into: strings and chars as they are,
+    /// anything else as it is printed.
+    pub fn str_value(&self) -> String {
+        match self {
+            Object::String(s) => s.to_string(),
+            Object::Char(c) => c.to_string(),
+            x => format!("{}", x),
+        }
+    }
+
+    /// The length of `n` copies of something `len` long, or `None` when that
+    /// is too large to build. Negative counts give nothing.
+    pub fn repeat_len(len: usize, n: i64) -> Option<usize> {
+        len.checked_mul(n.max(0) as usize)
+            .filter(|&total| total <= MAX_BUILD_LEN)
+    }
+
+    /// Whether the value can be used as a hash key.
+    pub fn is_hashable(&self) -> bool {
+        match self {
+            Object::Int(_)
+            | Object::BigInt(_)
+            | Object::Bool(_)
+            | Object::String(_)
+            | Object::Char(_) => true,
+            _ => false,
+        }
+    }
+
+    /// How many values a range has, without walking it. Widened to `i128`
+    /// because `range(-9223372036854775808, 9223372036854775807)` has more
+    /// values than an `i64` can count.
+    pub fn range_len(start: i64, end: i64, step: i64) -> i128 {
+        let (start, end, step) = (i128::from(start), i128::from(end), i128::from(step));
+        let span = if step > 0 { end - start } else { start - end };
+        if span <= 0 {
+            0
+        } else {
+            (span - 1) / step.abs() + 1
+        }
+    }
+
+    /// The values of a range, in order.
+    pub fn range_values(start: i64, end: i64, step: i64) -> impl Iterator<Item = i64> {
+        let mut next = Some(start);
+        std::iter::from_fn(move || {
+            let value = next.filter(|&v| if step > 0 { v < end } else { v > end })?;
+            next = value.checked_add(step);
+            Some(value)
+        })
+    }
+}
+
 impl Eq for Object {}
 
 impl Hash for Object {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match *self {
             Object::Int(ref i) => i.hash(state),
+            Object::BigInt(ref i) => i.hash(state),
             Object::Bool(ref b) => b.hash(state),
             Object::String(ref s) => s.hash(state),
+            Object::Char(ref c) => c.hash(state),
             _ => "".hash(state),
         }
     }
@@ -1,6 +1,7 @@
 #![allow(clippy::if_same_then_else)]
 use ast::*;
-use lexer::unescape::escape_str;
+use bigint::BigInt;
+use lexer::unescape::{escape_interp_str, escape_str};
 
 struct FormatConfig {
     max_line_length: usize,
@@ -13,6 +14,12 @@ pub struct Formatter {
     config: FormatConfig,
 }
 
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Formatter {
     pub fn new() -> Self {
         Formatter {
@@ -28,21 +35,24 @@ impl Formatter {
     fn infix_to_precedence(infix: &Infix) -> Precedence {
         match infix {
             Infix::Plus | Infix::Minus => Precedence::Sum,
-            Infix::Multiply | Infix::Divide => Precedence::Product,
+            Infix::Multiply | Infix::Divide | Infix::Modulo => Precedence::Product,
+            Infix::Power => Precedence::Power,
+            Infix::ShiftLeft | Infix::ShiftRight => Precedence::Shift,
+            Infix::BitAnd => Precedence::BitAnd,
+            Infix::BitXor => Precedence::BitXor,
+            Infix::BitOr => Precedence::BitOr,
             Infix::LessThan | Infix::LessThanEqual => Precedence::LessGreater,
             Infix::GreaterThan | Infix::GreaterThanEqual => Precedence::LessGreater,
+            Infix::In => Precedence::LessGreater,
             Infix::Equal | Infix::NotEqual => Precedence::Equals,
+            Infix::And => Precedence::And,
+            Infix::Or => Precedence::Or,
         }
     }
 
     fn ignore_semicolon_expr(expr: &Expr) -> bool {
-        match expr {
-            &Expr::If {
-                cond: _,
-                consequence: _,
-                alternative: _,
-            }
-            | &Expr::Func { params: _, body: _ } => true,
+        match expr.kind {
+            ExprKind::If { .. } | ExprKind::Func { .. } => true,
             _ => false,
         }
     }
@@ -63,11 +73,12 @@ impl Formatter {
             .iter()
             .enumerate()
             .filter_map(|(i, x)| {
-                if i == 0 && *x == Stmt::Blank {
+                if i == 0 && x.kind == StmtKind::Blank {
                     None
-                } else if i + 1 == stmts.len() && *x == Stmt::Blank {
+                } else if i + 1 == stmts.len() && x.kind == StmtKind::Blank {
                     None
-                } else if i > 0 && *x == Stmt::Blank && stmts.get(i - 1) == Some(&Stmt::Blank) {
+                } else if i > 0 && x.kind == StmtKind::Blank && stmts[i - 1].kind == StmtKind::Blank
+                {
                     None
                 } else {
                     Some(x.clone())
@@ -76,18 +87,66 @@ impl Formatter {
             .collect::<Vec<_>>()
     }
 
+    /// Moves the comments from inside a statement, say between the lines
+    /// of a multi-line array, to just before it. The statement is printed
+    /// afresh, so they would otherwise end up after it.
+    fn hoist_inner_comments(stmts: BlockStmt) -> BlockStmt {
+        let mut result: BlockStmt = vec![];
+        // Where the last statement that is not trivia sits in `result`.
+        let mut anchor: Option<usize> = None;
+
+        for stmt in stmts {
+            let is_comment = matches!(stmt.kind, StmtKind::Comment(_));
+            let start = stmt.span.start;
+
+            match anchor {
+                Some(i)
+                    if is_comment
+                        && start.offset < result[i].span.end.offset
+                        && start.line < result[i].span.end.line =>
+                {
+                    result.insert(i, stmt);
+                    anchor = Some(i + 1);
+                }
+                _ if stmt.is_trivia() => result.push(stmt),
+                _ => {
+                    anchor = Some(result.len());
+                    result.push(stmt);
+                }
+            }
+        }
+
+        result
+    }
+
     fn format_block_stmt(&mut self, stmts: BlockStmt) -> String {
         let mut result = String::new();
-        let list = Self::normalize_block_stmt(stmts);
+        let list = Self::normalize_block_stmt(Self::hoist_inner_comments(stmts));
+
+        let mut last_line = None;
 
         for (i, stmt) in list.into_iter().enumerate() {
             self.column = self.indent * 2 + 1;
 
+            // A comment starting on the line where the previous statement
+            // ends stays at the end of that line.
+            if let StmtKind::Comment(ref text) = stmt.kind {
+                if last_line == Some(stmt.span.start.line) {
+                    result.push_str(&format!(" {}", text));
+                    continue;
+                }
+            }
+
+            last_line = match stmt.kind {
+                StmtKind::Blank | StmtKind::Comment(_) => None,
+                _ => Some(stmt.span.end.line),
+            };
+
             if i > 0 {
                 result.push('\n');
             }
 
-            let indent_str = if stmt == Stmt::Blank {
+            let indent_str = if stmt.kind == StmtKind::Blank {
                 String::new()
             } else {
                 self.indent_str(0)
@@ -100,19 +159,21 @@ impl Formatter {
     }
 
     fn format_stmt(&mut self, stmt: Stmt) -> String {
-        match stmt {
-            Stmt::Let(ident, expr) => self.format_let_stmt(ident, expr),
-            Stmt::Return(expr) => self.format_return_stmt(expr),
-            Stmt::Break => String::from("break;"),
-            Stmt::Continue => String::from("continue;"),
-            Stmt::Expr(expr) => {
+        match stmt.kind {
+            StmtKind::Let(ident, expr) => self.format_let_stmt(ident, expr),
+            StmtKind::Assign { target, op, value } => self.format_assign_stmt(target, op, value),
+            StmtKind::Return(expr) => self.format_return_stmt(expr),
+            StmtKind::Break => String::from("break;"),
+            StmtKind::Continue => String::from("continue;"),
+            StmtKind::Expr(expr) => {
                 if Self::ignore_semicolon_expr(&expr) {
                     self.format_expr(expr, Precedence::Lowest)
                 } else {
                     format!("{};", self.format_expr(expr, Precedence::Lowest))
                 }
             }
-            Stmt::Blank => String::new(),
+            StmtKind::Comment(text) => text,
+            StmtKind::Blank => String::new(),
         }
     }
 
@@ -127,6 +188,20 @@ impl Formatter {
         format!("{}{};", result, expr_str)
     }
 
+    fn format_assign_stmt(&mut self, target: Expr, op: Option<Infix>, value: Expr) -> String {
+        let target_str = self.format_expr(target, Precedence::Lowest);
+        let result = match op {
+            Some(op) => format!("{} {}= ", target_str, op),
+            None => format!("{} = ", target_str),
+        };
+
+        self.column += result.len() - target_str.len();
+
+        let value_str = self.format_expr(value, Precedence::Lowest);
+
+        format!("{}{};", result, value_str)
+    }
+
     fn format_return_stmt(&mut self, expr: Expr) -> String {
         let result = String::from("return ");
 
@@ -136,22 +211,34 @@ impl Formatter {
     }
 
     fn format_expr(&mut self, expr: Expr, precedence: Precedence) -> String {
-        match expr {
-            Expr::Ident(ident) => self.format_ident_expr(ident),
-            Expr::Literal(literal) => self.format_literal(literal),
-            Expr::Prefix(prefix, right) => self.format_prefix_expr(prefix, right),
-            Expr::Infix(infix, left, right) => {
-                self.format_infix_expr(infix, left, right, precedence)
+        match expr.kind {
+            ExprKind::Ident(ident) => self.format_ident_expr(ident),
+            ExprKind::Literal(literal) => self.format_literal(literal),
+            ExprKind::Prefix(prefix, right) => self.format_prefix_expr(prefix, *right),
+            ExprKind::Infix(infix, left, right) => {
+                self.format_infix_expr(infix, *left, *right, precedence)
             }
-            Expr::Index(left, index) => self.format_index_expr(left, index),
-            Expr::If {
+            ExprKind::Index(left, index) => self.format_index_expr(*left, *index),
+            ExprKind::Slice {
+                left,
+                start,
+                end,
+                step,
+            } => self.format_slice_expr(*left, start, end, step),
+            ExprKind::Interpolated(parts) => self.format_interpolated_expr(parts),
+            ExprKind::If {
                 cond,
                 consequence,
                 alternative,
-            } => self.format_if_expr(cond, consequence, alternative),
-            Expr::While { cond, consequence } => self.format_while_expr(cond, consequence),
-            Expr::Func { params, body } => self.format_func_expr(params, body),
-            Expr::Call { func, args } => self.format_call_expr(func, args),
+            } => self.format_if_expr(*cond, consequence, alternative),
+            ExprKind::While { cond, consequence } => self.format_while_expr(*cond, consequence),
+            ExprKind::For {
+                vars,
+                iterable,
+                body,
+            } => self.format_for_expr(vars, *iterable, body),
+            ExprKind::Func { params, body } => self.format_func_expr(params, body),
+            ExprKind::Call { func, args } => self.format_call_expr(*func, args),
         }
     }
 
@@ -164,8 +251,13 @@ impl Formatter {
     fn format_literal(&mut self, literal: Literal) -> String {
         match literal {
             Literal::Int(value) => self.format_int_literal(value),
+            Literal::BigInt(value) => self.format_big_int_literal(value),
+            Literal::Float(value) => self.format_float_literal(value),
             Literal::String(value) => self.format_string_literal(value),
+            Literal::RawString(value, hashes) => self.format_raw_string_literal(value, hashes),
+            Literal::Char(value) => self.format_char_literal(value),
             Literal::Bool(value) => self.format_bool_literal(value),
+            Literal::Null => self.format_null_literal(),
             Literal::Array(value) => self.format_array_literal(value, false),
             Literal::Hash(value) => self.format_hash_literal(value, false),
         }
@@ -177,12 +269,41 @@ impl Formatter {
         result
     }
 
+    fn format_big_int_literal(&mut self, value: BigInt) -> String {
+        let result = value.to_string();
+        self.column += result.len();
+        result
+    }
+
+    fn format_float_literal(&mut self, value: f64) -> String {
+        let result = format!("{:?}", value);
+        self.column += result.len();
+        result
+    }
+
     fn format_string_literal(&mut self, value: String) -> String {
         let result = escape_str(&value);
         self.column += result.len();
         result
     }
 
+    fn format_raw_string_literal(&mut self, value: String, hashes: usize) -> String {
+        let result = format!("r{0}\"{1}\"{0}", "#".repeat(hashes), value);
+        self.column += result.len();
+        result
+    }
+
+    fn format_char_literal(&mut self, value: char) -> String {
+        let result = format!("{:?}", value);
+        self.column += result.len();
+        result
+    }
+
+    fn format_null_literal(&mut self) -> String {
+        self.column += 4;
+        String::from("null")
+    }
+
     fn format_bool_literal(&mut self, value: bool) -> String {
         let result = value.to_string();
         self.column += result.len();
@@ -286,13 +407,14 @@ impl Formatter {
     fn format_infix_expr(
         &mut self,
         infix: Infix,
-        left: Box<Expr>,
-        right: Box<Expr>,
+        left: Expr,
+        right: Expr,
         precedence: Precedence,
     ) -> String {
         let current_precedence = Self::infix_to_precedence(&infix);
-        let left_str = self.format_expr(*left, current_precedence.clone());
-        let right_str = self.format_expr(*right, current_precedence.clone());
+        let right_assoc = infix == Infix::Power;
+        let left_str = self.format_operand(left, &current_precedence, right_assoc);
+        let right_str = self.format_operand(right, &current_precedence, !right_assoc);
 
         if precedence > current_precedence {
             format!("({} {} {})", left_str, infix, right_str)
@@ -301,32 +423,121 @@ impl Formatter {
         }
     }
 
-    fn format_prefix_expr(&mut self, prefix: Prefix, right: Box<Expr>) -> String {
-        let right_str = self.format_expr(*right, Precedence::Prefix);
+    /// Formats an operand of an infix operator with `precedence`. `inner` is
+    /// set for the side the operator does not group towards, which needs
+    /// parentheses even at the same precedence: `a - (b - c)`, `(a ** b) ** c`.
+    fn format_operand(&mut self, expr: Expr, precedence: &Precedence, inner: bool) -> String {
+        let parens = match expr.kind {
+            ExprKind::Infix(ref infix, _, _) => {
+                inner && Self::infix_to_precedence(infix) == *precedence
+            }
+            ExprKind::Prefix(_, _) => *precedence > Precedence::Prefix,
+            _ => false,
+        };
+        let result = self.format_expr(expr, precedence.clone());
+
+        if parens {
+            format!("({})", result)
+        } else {
+            result
+        }
+    }
+
+    fn format_prefix_expr(&mut self, prefix: Prefix, right: Expr) -> String {
+        let right_str = self.format_expr(right, Precedence::Prefix);
 
         format!("{}{}", prefix, right_str)
     }
 
-    fn format_index_expr(&mut self, left: Box<Expr>, index: Box<Expr>) -> String {
-        let left_str = self.format_expr(*left, Precedence::Lowest);
-        let index_str = self.format_expr(*index, Precedence::Lowest);
+    fn format_index_expr(&mut self, left: Expr, index: Expr) -> String {
+        let left_str = self.format_expr(left, Precedence::Lowest);
+        let index_str = self.format_expr(index, Precedence::Lowest);
 
         format!("{}[{}]", left_str, index_str)
     }
 
+    fn format_slice_expr(
+        &mut self,
+        left: Expr,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+        step: Option<Box<Expr>>,
+    ) -> String {
+        let left_str = self.format_expr(left, Precedence::Lowest);
+        let mut bound_str = |bound: Option<Box<Expr>>| match bound {
+            Some(bound) => self.format_expr(*bound, Precedence::Lowest),
+            None => String::new(),
+        };
+        let start_str = bound_str(start);
+        let end_str = bound_str(end);
+
+        match step {
+            Some(step) => format!(
+                "{}[{}:{}:{}]",
+                left_str,
+                start_str,
+                end_str,
+                bound_str(Some(step))
+            ),
+            None => format!("{}[{}:{}]", left_str, start_str, end_str),
+        }
+    }
+
+    fn format_interpolated_expr(&mut self, parts: Vec<StringPart>) -> String {
+        let mut result = String::from("f\"");
+        self.column += 2;
+
+        for part in parts {
+            let part_str = match part {
+                StringPart::Text(text) => {
+                    let text_str = escape_interp_str(&text);
+                    self.column += text_str.len();
+                    text_str
+                }
+                StringPart::Expr(expr) => {
+                    self.column += 1;
+                    let expr_str = self.format_expr(expr, Precedence::Lowest);
+                    self.column += 1;
+                    // `{{` would be read back as a literal brace.
+                    if expr_str.starts_with('{') {
+                        self.column += 1;
+                        format!("{{ {}}}", expr_str)
+                    } else {
+                        format!("{{{}}}", expr_str)
+                    }
+                }
+            };
+            result.push_str(&part_str);
+        }
+
+        self.column += 1;
+        result.push('"');
+        result
+    }
+
     fn format_if_expr(
         &mut self,
-        cond: Box<Expr>,
+        cond: Expr,
         consequence: BlockStmt,
         alternative: Option<BlockStmt>,
     ) -> String {
-        let cond_str = self.format_expr(*cond, Precedence::Lowest);
+        let cond_str = self.format_expr(cond, Precedence::Lowest);
 
         self.indent += 1;
 
         let consequence_str = self.format_block_stmt(consequence);
 
         let result = match alternative {
+            Some(mut alternative_expr) if Self::is_else_if(&alternative_expr) => {
+                let indent_str = self.indent_str(-1);
+                self.indent -= 1;
+                let else_if_str = self.format_stmt(alternative_expr.remove(0));
+                self.indent += 1;
+                format!(
+                    "if ({}) {{\n{}\n{}}} else {}",
+                    cond_str, consequence_str, indent_str, else_if_str,
+                )
+            }
             Some(alternative_expr) => {
                 let alternative_str = self.format_block_stmt(alternative_expr);
                 let indent_str = self.indent_str(-1);
@@ -349,8 +560,24 @@ impl Formatter {
         result
     }
 
-    fn format_while_expr(&mut self, cond: Box<Expr>, consequence: BlockStmt) -> String {
-        let cond_str = self.format_expr(*cond, Precedence::Lowest);
+    /// True if an `else` block holds nothing but another `if`, which is
+    /// then printed as `else if`.
+    fn is_else_if(block: &[Stmt]) -> bool {
+        match block {
+            [Stmt {
+                kind:
+                    StmtKind::Expr(Expr {
+                        kind: ExprKind::If { .. },
+                        ..
+                    }),
+                ..
+            }] => true,
+            _ => false,
+        }
+    }
+
+    fn format_while_expr(&mut self, cond: Expr, consequence: BlockStmt) -> String {
+        let cond_str = self.format_expr(cond, Precedence::Lowest);
         self.indent += 1;
 
         let consequence_str = self.format_block_stmt(consequence);
@@ -364,6 +591,25 @@ impl Formatter {
         result
     }
 
+    fn format_for_expr(&mut self, vars: Vec<Ident>, iterable: Expr, body: BlockStmt) -> String {
+        let vars_str = vars
+            .into_iter()
+            .map(|ident| self.format_ident_expr(ident))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let iterable_str = self.format_expr(iterable, Precedence::Lowest);
+        self.indent += 1;
+
+        let body_str = self.format_block_stmt(body);
+        let indent_str = self.indent_str(-1);
+        self.indent -= 1;
+
+        format!(
+            "for ({} in {}) {{\n{}\n{}}}",
+            vars_str, iterable_str, body_str, indent_str
+        )
+    }
+
     fn format_func_expr(&mut self, params: Vec<Ident>, body: BlockStmt) -> String {
         let mut params_str = String::new();
 
@@ -389,8 +635,8 @@ impl Formatter {
         )
     }
 
-    fn format_call_expr(&mut self, func: Box<Expr>, args: Vec<Expr>) -> String {
-        let func_str = self.format_expr(*func, Precedence::Lowest);
+    fn format_call_expr(&mut self, func: Expr, args: Vec<Expr>) -> String {
+        let func_str = self.format_expr(func, Precedence::Lowest);
         let mut args_str = String::new();
 
         for (i, arg) in args.into_iter().enumerate() {
@@ -463,7 +709,19 @@ mod tests {
     fn test_literal() {
         let tests = vec![
             ("1000", "1000;"),
+            ("99999999999999999999", "99999999999999999999;"),
+            ("3.750", "3.75;"),
+            ("1e3", "1000.0;"),
             ("\"foo\"", "\"foo\";"),
+            ("f\"得分: {  score  }\"", "f\"得分: {score}\";"),
+            ("f\"{{x}} {a+b}{ {\"k\":1}[\"k\"]}\"", "f\"{{x}} {a + b}{ { \"k\": 1 }[\"k\"]}\";"),
+            ("f\"\\u{7b}\"", "f\"{{\";"),
+            ("'a'", "'a';"),
+            ("'\\u{41}'", "'A';"),
+            ("'\\''", "'\\'';"),
+            ("r\"C:\\n\"", "r\"C:\\n\";"),
+            ("r##\"say \"#hi\"\"##", "r##\"say \"#hi\"\"##;"),
+            ("留白", "null;"),
             ("true", "true;"),
             ("false", "false;"),
             (
@@ -557,6 +815,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_assign_stmt() {
+        let tests = vec![
+            ("x=1", "x = 1;"),
+            ("x  +=  y*2", "x += y * 2;"),
+            ("arr[i+1] -= 1;", "arr[i + 1] -= 1;"),
+            ("h.key = [1,2]", "h[\"key\"] = [1, 2];"),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(String::from(expect), format(input));
+        }
+    }
+
     #[test]
     fn test_return_stmt() {
         let tests = vec![
@@ -581,6 +853,20 @@ mod tests {
             ("(2 + 2)   * 5", "(2 + 2) * 5;"),
             ("2/(5+5  )", "2 / (5 + 5);"),
             ("2   / 5+5  ", "2 / 5 + 5;"),
+            ("a||b  &&c==d", "a || b && c == d;"),
+            ("(a 兜底 b) 协同 c", "(a || b) && c;"),
+            ("a-(b-c)", "a - (b - c);"),
+            ("(a-b)-c", "a - b - c;"),
+            ("a 余量 b 杠杆 c", "a % b ** c;"),
+            ("(a**b)**c", "(a ** b) ** c;"),
+            ("a**(b**c)", "a ** b ** c;"),
+            ("(-a)**b", "(-a) ** b;"),
+            ("-a**b", "-a ** b;"),
+            ("a&b|c^d", "a & b | c ^ d;"),
+            ("(a|b)&c", "(a | b) & c;"),
+            ("1<<2 > 3>>1", "1 << 2 > 3 >> 1;"),
+            ("~ a", "~a;"),
+            ("a 沉淀  [1]  放大 2", "a in [1] * 2;"),
             // prefix
             ("-  5", "-5;"),
             ("! true", "!true;"),
@@ -599,6 +885,12 @@ mod tests {
             ("foo[ 0  ]", "foo[0];"),
             ("foo[   1*2 ]", "foo[1 * 2];"),
             ("foo [   \"key\" ]", "foo[\"key\"];"),
+            ("foo[ 1 : 2 ]", "foo[1:2];"),
+            ("foo[:-1]", "foo[:-1];"),
+            ("foo[ 1 : ]", "foo[1:];"),
+            ("foo[:]", "foo[:];"),
+            ("foo[::  -1]", "foo[::-1];"),
+            ("foo[a+1:b:2]", "foo[a + 1:b:2];"),
         ];
 
         for (input, expect) in tests {
@@ -664,20 +956,37 @@ if(         y){if(z)        { z; }}
     if (z) {
       z;
     }
-  } else {
-    if (z) {
-      z;
-    }
+  } else if (z) {
+    z;
   }
+} else if (y) {
+  if (z) {
+    z;
+  }
+} else if (z) {
+  z;
+}"#,
+            ),
+            (
+                "if(a){1}else if(b){2}else 细分(c){3}路径{4}",
+                r#"if (a) {
+  1;
+} else if (b) {
+  2;
+} else if (c) {
+  3;
 } else {
-  if (y) {
-    if (z) {
-      z;
-    }
-  } else {
-    if (z) {
-      z;
-    }
+  4;
+}"#,
+            ),
+            (
+                "if(a){1}else{// keep the braces\nif(b){2}}",
+                r#"if (a) {
+  1;
+} else {
+  // keep the braces
+  if (b) {
+    2;
   }
 }"#,
             ),
@@ -738,6 +1047,28 @@ continue
         }
     }
 
+    #[test]
+    fn test_for_expr() {
+        let tests = vec![
+            (
+                "for(x in xs){puts(x)}",
+                r#"for (x in xs) {
+  puts(x);
+};"#,
+            ),
+            (
+                "拉通 (k,v 沉淀 h) { 破圈 }",
+                r#"for (k, v in h) {
+  break;
+};"#,
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(String::from(expect), format(input));
+        }
+    }
+
     #[test]
     fn test_call_expr() {
         let tests = vec![
@@ -766,4 +1097,88 @@ continue
             assert_eq!(String::from(expect), format(input));
         }
     }
+
+    #[test]
+    fn test_comment() {
+        let tests = vec![
+            (
+                "// header\nlet   x = 1;   // one\n\n\n/* block */\nx",
+                r#"// header
+let x = 1; // one
+
+/* block */
+x;"#,
+            ),
+            (
+                r#"fn(x) {
+    // inside
+  x;  划重点 返回 x
+    /* last
+  line */
+}"#,
+                r#"fn(x) {
+  // inside
+  x; 划重点 返回 x
+  /* last
+  line */
+}"#,
+            ),
+            (
+                r#"if (x) {
+  y;
+} // done
+let z = [1, // first
+2];"#,
+                r#"if (x) {
+  y;
+} // done
+// first
+let z = [1, 2];"#,
+            ),
+            (
+                r#"let total = add(
+  1, // one
+  /* two */ 2
+);
+total"#,
+                r#"// one
+/* two */
+let total = add(1, 2);
+total;"#,
+            ),
+            (
+                r#"if (x) {
+  y;
+} // not x
+else {
+  z;
+}"#,
+                r#"if (x) {
+  y;
+  // not x
+} else {
+  z;
+}"#,
+            ),
+            (
+                r#"if (x) {
+  y;
+}
+// otherwise
+else if (z) {
+  z;
+}"#,
+                r#"if (x) {
+  y;
+  // otherwise
+} else if (z) {
+  z;
+}"#,
+            ),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(String::from(expect), format(input));
+        }
+    }
 }
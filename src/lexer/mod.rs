@@ -2,10 +2,57 @@ extern crate unicode_normalization;
 /// Unicode lexer for the PUA language.
 /// Some functions taken from `rust/compiler/rustc_lexer/src/lib.rs`.
 extern crate unicode_xid;
-use crate::token::Token;
+use self::unescape::EscapeError;
+use span::{Pos, Span};
+use std::fmt;
+use std::ops::Range;
+use token::{SpannedToken, Token};
 
 pub mod unescape;
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    UnterminatedString { span: Span },
+    UnterminatedChar { span: Span },
+    UnterminatedComment { span: Span },
+    InvalidEscape { kind: EscapeError, span: Span },
+    IllegalCharacter { ch: char, span: Span },
+}
+
+impl LexError {
+    pub fn span(&self) -> Span {
+        match self {
+            LexError::UnterminatedString { span }
+            | LexError::UnterminatedChar { span }
+            | LexError::UnterminatedComment { span }
+            | LexError::InvalidEscape { span, .. }
+            | LexError::IllegalCharacter { span, .. } => *span,
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LexError::UnterminatedString { span } => {
+                write!(f, "{}: Unterminated string literal", span)
+            }
+            LexError::UnterminatedChar { span } => {
+                write!(f, "{}: Unterminated char literal", span)
+            }
+            LexError::UnterminatedComment { span } => {
+                write!(f, "{}: Unterminated block comment", span)
+            }
+            LexError::InvalidEscape { kind, span } => {
+                write!(f, "{}: Invalid escape in string literal: {:?}", span, kind)
+            }
+            LexError::IllegalCharacter { ch, span } => {
+                write!(f, "{}: Illegal character: {:?}", span, ch)
+            }
+        }
+    }
+}
+
 /// All variable names are nfc-normaized.
 pub fn nfc_normalize(string: &str) -> String {
     use self::unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
@@ -75,8 +122,7 @@ fn is_emoji_like(c: char) -> bool {
 /// True if `c` is valid as a first character of an identifier.
 /// Compared to Rust, we additionally allow $ and ¥.
 fn is_id_start(c: char) -> bool {
-    ('a'..='z').contains(&c)
-        || ('A'..='Z').contains(&c)
+    c.is_ascii_alphabetic()
         || c == '_'
         || c == '$'
         || c == '¥'
@@ -87,9 +133,7 @@ fn is_id_start(c: char) -> bool {
 /// True if `c` is valid as a non-first character of an identifier.
 /// Compared to Rust, we additionally allow $ and ¥.
 fn is_id_continue(c: char) -> bool {
-    ('a'..='z').contains(&c)
-        || ('A'..='Z').contains(&c)
-        || ('0'..='9').contains(&c)
+    c.is_ascii_alphanumeric()
         || c == '_'
         || c == '$'
         || c == '¥'
@@ -102,6 +146,11 @@ pub struct Lexer {
     pos: usize,
     next_pos: usize,
     ch: char,
+    /// Source location of `ch`.
+    loc: Pos,
+    /// One entry for each `f"..."` hole being lexed, counting the `{`s
+    /// opened inside it, so the `}` that closes the hole can be told apart.
+    holes: Vec<usize>,
 }
 
 impl Lexer {
@@ -112,6 +161,8 @@ impl Lexer {
             pos: 0,
             next_pos: 0,
             ch: '\0',
+            loc: Pos::default(),
+            holes: vec![],
         };
 
         lexer.read_char();
@@ -120,6 +171,9 @@ impl Lexer {
     }
 
     fn read_char(&mut self) {
+        if self.next_pos > 0 && self.pos < self.input.len() {
+            self.loc.advance(self.ch);
+        }
         if self.next_pos >= self.input.len() {
             self.ch = '\0';
         } else {
@@ -129,7 +183,7 @@ impl Lexer {
         self.next_pos += 1;
     }
 
-    fn nextch(&mut self) -> char {
+    fn nextch(&self) -> char {
         if self.next_pos >= self.input.len() {
             '\0'
         } else {
@@ -137,23 +191,31 @@ impl Lexer {
         }
     }
 
-    fn nextch_is(&mut self, ch: char) -> bool {
+    fn nextch_is(&self, ch: char) -> bool {
         self.nextch() == ch
     }
 
+    /// Skips whitespace and lone newlines. A newline followed by another
+    /// newline is kept, as it starts a `Token::Blank`.
     fn skip_whitespace(&mut self) {
-        loop {
-            if is_whitespace(self.ch) {
-                self.read_char();
-            } else {
-                break;
-            }
+        while is_whitespace(self.ch) || (self.ch == '\n' && !self.nextch_is('\n')) {
+            self.read_char();
         }
     }
 
-    pub fn next_token(&mut self) -> Token {
+    pub fn next_token(&mut self) -> Result<SpannedToken, LexError> {
         self.skip_whitespace();
 
+        let start = self.loc;
+        let token = self.read_token()?;
+
+        Ok(SpannedToken {
+            token,
+            span: Span::new(start, self.loc),
+        })
+    }
+
+    fn read_token(&mut self) -> Result<Token, LexError> {
         let tok = match self.ch {
             '=' => {
                 if self.nextch_is('=') {
@@ -163,8 +225,22 @@ impl Lexer {
                     Token::Assign
                 }
             }
-            '+' => Token::Plus,
-            '-' => Token::Minus,
+            '+' => {
+                if self.nextch_is('=') {
+                    self.read_char();
+                    Token::PlusAssign
+                } else {
+                    Token::Plus
+                }
+            }
+            '-' => {
+                if self.nextch_is('=') {
+                    self.read_char();
+                    Token::MinusAssign
+                } else {
+                    Token::Minus
+                }
+            }
             '!' => {
                 if self.nextch_is('=') {
                     self.read_char();
@@ -173,12 +249,50 @@ impl Lexer {
                     Token::Bang
                 }
             }
-            '/' => Token::Slash,
-            '*' => Token::Asterisk,
+            '/' => {
+                if self.nextch_is('/') {
+                    return Ok(self.consume_line_comment(self.pos));
+                } else if self.nextch_is('*') {
+                    return self.consume_block_comment();
+                } else if self.nextch_is('=') {
+                    self.read_char();
+                    Token::SlashAssign
+                } else {
+                    Token::Slash
+                }
+            }
+            // A `#!` line at the very start lets scripts run as executables.
+            '#' if self.pos == 0 && self.nextch_is('!') => {
+                return Ok(self.consume_line_comment(self.pos));
+            }
+            '*' => {
+                if self.nextch_is('=') {
+                    self.read_char();
+                    Token::AsteriskAssign
+                } else if self.nextch_is('*') {
+                    self.read_char();
+                    if self.nextch_is('=') {
+                        self.read_char();
+                        Token::DoubleAsteriskAssign
+                    } else {
+                        Token::DoubleAsterisk
+                    }
+                } else {
+                    Token::Asterisk
+                }
+            }
             '<' => {
                 if self.nextch_is('=') {
                     self.read_char();
                     Token::LessThanEqual
+                } else if self.nextch_is('<') {
+                    self.read_char();
+                    if self.nextch_is('=') {
+                        self.read_char();
+                        Token::ShiftLeftAssign
+                    } else {
+                        Token::ShiftLeft
+                    }
                 } else {
                     Token::LessThan
                 }
@@ -187,14 +301,57 @@ impl Lexer {
                 if self.nextch_is('=') {
                     self.read_char();
                     Token::GreaterThanEqual
+                } else if self.nextch_is('>') {
+                    self.read_char();
+                    if self.nextch_is('=') {
+                        self.read_char();
+                        Token::ShiftRightAssign
+                    } else {
+                        Token::ShiftRight
+                    }
                 } else {
                     Token::GreaterThan
                 }
             }
+            '&' if self.nextch_is('&') => {
+                self.read_char();
+                Token::And
+            }
+            '|' if self.nextch_is('|') => {
+                self.read_char();
+                Token::Or
+            }
+            '&' | '|' | '%' | '^' if self.nextch_is('=') => {
+                let tok = match self.ch {
+                    '&' => Token::AmpersandAssign,
+                    '|' => Token::PipeAssign,
+                    '%' => Token::PercentAssign,
+                    _ => Token::CaretAssign,
+                };
+                self.read_char();
+                tok
+            }
+            '&' => Token::Ampersand,
+            '|' => Token::Pipe,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '~' => Token::Tilde,
             '(' => Token::Lparen,
             ')' => Token::Rparen,
-            '{' => Token::Lbrace,
-            '}' => Token::Rbrace,
+            '{' => {
+                if let Some(depth) = self.holes.last_mut() {
+                    *depth += 1;
+                }
+                Token::Lbrace
+            }
+            '}' => match self.holes.last_mut() {
+                Some(0) => return self.consume_interp_text(false),
+                Some(depth) => {
+                    *depth -= 1;
+                    Token::Rbrace
+                }
+                None => Token::Rbrace,
+            },
             '[' => Token::Lbracket,
             ']' => Token::Rbracket,
             '.' => Token::Dot,
@@ -202,43 +359,92 @@ impl Lexer {
             ';' => Token::Semicolon,
             ':' => Token::Colon,
             '0'..='9' => {
-                return self.consume_number();
+                return Ok(self.consume_number());
             }
             '"' => {
                 return self.consume_string();
             }
-            '\n' => {
-                if self.nextch_is('\n') {
-                    Token::Blank
-                } else {
-                    self.read_char();
-                    return self.next_token();
-                }
+            '\'' => {
+                return self.consume_char();
             }
+            'f' if self.nextch_is('"') => {
+                return self.consume_interp_text(true);
+            }
+            'r' if self.nextch_is('"') || self.nextch_is('#') => {
+                return self.consume_raw_string();
+            }
+            '\n' => Token::Blank,
             '\0' => Token::Eof,
             _ => {
                 if is_id_start(self.ch) {
-                    return self.consume_identifier();
+                    return Ok(self.consume_identifier());
                 } else {
-                    Token::Illegal
+                    let (ch, start) = (self.ch, self.loc);
+                    self.read_char();
+                    return Err(LexError::IllegalCharacter {
+                        ch,
+                        span: Span::new(start, self.loc),
+                    });
                 }
             }
         };
 
         self.read_char();
 
-        tok
+        Ok(tok)
     }
 
+    /// Every word the lexer reads as something other than an identifier,
+    /// in both the Monkey and the Aba-aba dialect.
+    pub const KEYWORDS: &'static [&'static str] = &[
+        "fn",
+        "let",
+        "true",
+        "false",
+        "null",
+        "if",
+        "while",
+        "for",
+        "in",
+        "break",
+        "continue",
+        "else",
+        "return",
+        "抓手",
+        "赋能",
+        "三七五",
+        "三二五",
+        "留白",
+        "细分",
+        "路径",
+        "闭环",
+        "拉通",
+        "沉淀",
+        "破圈",
+        "反哺",
+        "对齐",
+        "联动",
+        "差异",
+        "放大",
+        "倾斜",
+        "协同",
+        "兜底",
+        "余量",
+        "杠杆",
+        "耦合",
+        "并联",
+        "错位",
+        "颠覆",
+        "升维",
+        "降维",
+        "划重点",
+    ];
+
     fn consume_identifier(&mut self) -> Token {
         let start_pos = self.pos;
 
-        loop {
-            if is_id_continue(self.ch) {
-                self.read_char();
-            } else {
-                break;
-            }
+        while is_id_continue(self.ch) {
+            self.read_char();
         }
 
         let literal = self.input[start_pos..self.pos].iter().collect::<String>();
@@ -249,8 +455,11 @@ impl Lexer {
             "let" => Token::Let,
             "true" => Token::Bool(true),
             "false" => Token::Bool(false),
+            "null" => Token::Null,
             "if" => Token::If,
             "while" => Token::While,
+            "for" => Token::For,
+            "in" => Token::In,
             "break" => Token::Break,
             "continue" => Token::Continue,
             "else" => Token::Else,
@@ -260,42 +469,127 @@ impl Lexer {
             "赋能" => Token::Let,
             "三七五" => Token::Bool(true),
             "三二五" => Token::Bool(false),
+            "留白" => Token::Null,
             "细分" => Token::If,
             "路径" => Token::Else,
             "闭环" => Token::While,
+            "拉通" => Token::For,
+            "沉淀" => Token::In,
             "破圈" => Token::Break,
             "反哺" => Token::Return,
             "对齐" => Token::Equal,
             "联动" => Token::Plus,
             "差异" => Token::Minus,
+            "放大" => Token::Asterisk,
             "倾斜" => Token::Slash,
+            "协同" => Token::And,
+            "兜底" => Token::Or,
+            "余量" => Token::Percent,
+            "杠杆" => Token::DoubleAsterisk,
+            "耦合" => Token::Ampersand,
+            "并联" => Token::Pipe,
+            "错位" => Token::Caret,
+            "颠覆" => Token::Tilde,
+            "升维" => Token::ShiftLeft,
+            "降维" => Token::ShiftRight,
+            "划重点" => self.consume_line_comment(start_pos),
             _ => Token::Ident(nfc_normalize(&literal)),
         }
     }
 
-    fn consume_number(&mut self) -> Token {
+    /// Reads a comment running from `start_pos` to the end of the line.
+    fn consume_line_comment(&mut self, start_pos: usize) -> Token {
+        while self.ch != '\n' && self.ch != '\0' {
+            self.read_char();
+        }
+
+        let literal = self.input[start_pos..self.pos].iter().collect::<String>();
+
+        Token::Comment(literal.trim_end().to_string())
+    }
+
+    fn consume_block_comment(&mut self) -> Result<Token, LexError> {
         let start_pos = self.pos;
+        let start = self.loc;
+
+        self.read_char();
+        self.read_char();
 
         loop {
             match self.ch {
-                '0'..='9' => {
-                    self.read_char();
+                '\0' => {
+                    return Err(LexError::UnterminatedComment {
+                        span: Span::new(start, self.loc),
+                    })
                 }
-                _ => {
+                '*' if self.nextch_is('/') => {
+                    self.read_char();
+                    self.read_char();
                     break;
                 }
+                _ => self.read_char(),
             }
         }
 
-        let literal = &self.input[start_pos..self.pos].iter().collect::<String>();
+        let literal = self.input[start_pos..self.pos].iter().collect::<String>();
 
-        Token::Int(literal.parse::<i64>().unwrap())
+        Ok(Token::Comment(literal))
     }
 
-    fn consume_string(&mut self) -> Token {
+    fn consume_number(&mut self) -> Token {
+        let start_pos = self.pos;
+
+        while self.ch.is_ascii_digit() {
+            self.read_char();
+        }
+
+        let mut is_float = false;
+
+        if self.ch == '.' && self.nextch().is_ascii_digit() {
+            is_float = true;
+            self.read_char();
+            while self.ch.is_ascii_digit() {
+                self.read_char();
+            }
+        }
+
+        if self.ch == 'e' || self.ch == 'E' {
+            let next = self.nextch();
+            let after_sign = self.input.get(self.next_pos + 1).cloned().unwrap_or('\0');
+            if next.is_ascii_digit()
+                || ((next == '+' || next == '-') && after_sign.is_ascii_digit())
+            {
+                is_float = true;
+                self.read_char();
+                self.read_char();
+                while self.ch.is_ascii_digit() {
+                    self.read_char();
+                }
+            }
+        }
+
+        let literal = self.input[start_pos..self.pos].iter().collect::<String>();
+
+        if is_float {
+            // Only fails on malformed input, which the loops above rule out.
+            return Token::Float(literal.parse().unwrap_or(f64::NAN));
+        }
+
+        // The literal is all digits, so the only way it fails to parse as an
+        // `i64` is by being too large for one.
+        match literal.parse::<i64>() {
+            Ok(value) => Token::Int(value),
+            Err(_) => Token::BigInt(literal.parse().unwrap_or_default()),
+        }
+    }
+
+    fn consume_string(&mut self) -> Result<Token, LexError> {
+        let start = self.loc;
+
         self.read_char();
 
         let start_pos = self.pos;
+        let content_start = self.loc;
         let mut bs = false;
 
         while self.ch != '\0' {
@@ -304,9 +598,9 @@ impl Lexer {
             } else {
                 match self.ch {
                     '"' => {
-                        let literal = self.input[start_pos..self.pos].iter().collect::<String>();
+                        let literal = self.unescape_from(start_pos, content_start);
                         self.read_char();
-                        return Token::String(unescape::unescape_str_or_byte_str_all(&literal));
+                        return literal.map(Token::String);
                     }
                     '\\' => {
                         bs = true;
@@ -316,14 +610,208 @@ impl Lexer {
             }
             self.read_char();
         }
-        // FIXME: Make Lexer faliable
-        Token::String("<Lexer error: string: premature EOF>".to_string())
+
+        Err(LexError::UnterminatedString {
+            span: Span::new(start, self.loc),
+        })
+    }
+
+    /// Reads the text of an `f"..."` string, from just after `f` or the `}`
+    /// closing a hole, up to the next hole or the closing quote. `{{` and
+    /// `}}` stand for literal braces.
+    fn consume_interp_text(&mut self, head: bool) -> Result<Token, LexError> {
+        let start = self.loc;
+
+        self.read_char();
+        if head {
+            self.read_char();
+        }
+
+        let mut text = String::new();
+        let mut chunk_pos = self.pos;
+        let mut chunk_start = self.loc;
+        let mut bs = false;
+        // Inside a `\u{7FFF}` escape, whose braces are not holes.
+        let mut unicode = false;
+        // A bad escape is reported only once the text has been read up to
+        // its delimiter, so that lexing carries on after it.
+        let mut error = None;
+
+        while self.ch != '\0' {
+            if bs {
+                bs = false;
+                unicode = self.ch == 'u';
+            } else if unicode && self.ch != '"' {
+                unicode = self.ch != '}';
+            } else {
+                match self.ch {
+                    '\\' => bs = true,
+                    '{' | '}' if self.nextch_is(self.ch) => {
+                        match self.unescape_from(chunk_pos, chunk_start) {
+                            Ok(chunk) => text.push_str(&chunk),
+                            Err(err) => {
+                                error.get_or_insert(err);
+                            }
+                        }
+                        text.push(self.ch);
+                        self.read_char();
+                        self.read_char();
+                        chunk_pos = self.pos;
+                        chunk_start = self.loc;
+                        continue;
+                    }
+                    '{' | '"' => {
+                        match self.unescape_from(chunk_pos, chunk_start) {
+                            Ok(chunk) => text.push_str(&chunk),
+                            Err(err) => {
+                                error.get_or_insert(err);
+                            }
+                        }
+                        let hole = self.ch == '{';
+                        self.read_char();
+                        let token = match (head, hole) {
+                            (true, false) => Token::InterpString(text),
+                            (true, true) => {
+                                self.holes.push(0);
+                                Token::InterpStart(text)
+                            }
+                            (false, true) => Token::InterpMid(text),
+                            (false, false) => {
+                                self.holes.pop();
+                                Token::InterpEnd(text)
+                            }
+                        };
+                        return match error {
+                            Some(err) => Err(err),
+                            None => Ok(token),
+                        };
+                    }
+                    _ => (),
+                }
+            }
+            self.read_char();
+        }
+
+        Err(LexError::UnterminatedString {
+            span: Span::new(start, self.loc),
+        })
+    }
+
+    /// Unescapes the input from `start_pos`, at `start`, up to `ch`.
+    fn unescape_from(&self, start_pos: usize, start: Pos) -> Result<String, LexError> {
+        let literal = self.input[start_pos..self.pos].iter().collect::<String>();
+        unescape::unescape_str_or_byte_str_all(&literal).map_err(|(range, kind)| {
+            LexError::InvalidEscape {
+                kind,
+                span: content_span(start, &literal, range),
+            }
+        })
     }
+
+    fn consume_char(&mut self) -> Result<Token, LexError> {
+        let start = self.loc;
+
+        self.read_char();
+
+        let start_pos = self.pos;
+        let content_start = self.loc;
+        let mut bs = false;
+
+        while self.ch != '\0' && self.ch != '\n' {
+            if bs {
+                bs = false;
+            } else if self.ch == '\\' {
+                bs = true;
+            } else if self.ch == '\'' {
+                let literal = self.input[start_pos..self.pos].iter().collect::<String>();
+                self.read_char();
+                return unescape::unescape_char(&literal).map(Token::Char).map_err(
+                    |(range, kind)| LexError::InvalidEscape {
+                        kind,
+                        // `''` has no content to point at, so take the quotes.
+                        span: match kind {
+                            EscapeError::ZeroChars => Span::new(start, self.loc),
+                            _ => content_span(content_start, &literal, range),
+                        },
+                    },
+                );
+            }
+            self.read_char();
+        }
+
+        Err(LexError::UnterminatedChar {
+            span: Span::new(start, self.loc),
+        })
+    }
+
+    /// Reads `r"..."`, or `r#"..."#` with any number of `#`s so the string
+    /// may contain `"#`. Nothing is unescaped.
+    fn consume_raw_string(&mut self) -> Result<Token, LexError> {
+        let start = self.loc;
+
+        self.read_char();
+
+        let mut hashes = 0;
+        while self.ch == '#' {
+            hashes += 1;
+            self.read_char();
+        }
+
+        if self.ch != '"' {
+            return Err(LexError::IllegalCharacter {
+                ch: '#',
+                span: Span::new(start, self.loc),
+            });
+        }
+
+        self.read_char();
+
+        let start_pos = self.pos;
+        let content_start = self.loc;
+
+        while self.ch != '\0' {
+            let closed =
+                self.ch == '"' && (1..=hashes).all(|i| self.input.get(self.pos + i) == Some(&'#'));
+
+            if closed {
+                let literal = self.input[start_pos..self.pos].iter().collect::<String>();
+                for _ in 0..=hashes {
+                    self.read_char();
+                }
+                return match literal.find('\r') {
+                    Some(offset) => Err(LexError::InvalidEscape {
+                        kind: EscapeError::BareCarriageReturnInRawString,
+                        span: content_span(content_start, &literal, offset..offset + 1),
+                    }),
+                    None => Ok(Token::RawString(literal, hashes)),
+                };
+            }
+            self.read_char();
+        }
+
+        Err(LexError::UnterminatedString {
+            span: Span::new(start, self.loc),
+        })
+    }
+}
+
+/// The span of `range`, a byte range within `literal`, where `literal`
+/// starts at `start`.
+fn content_span(start: Pos, literal: &str, range: Range<usize>) -> Span {
+    let mut range_start = start;
+    literal[..range.start]
+        .chars()
+        .for_each(|c| range_start.advance(c));
+    let mut range_end = range_start;
+    literal[range].chars().for_each(|c| range_end.advance(c));
+    Span::new(range_start, range_end)
 }
 
 #[cfg(test)]
 mod tests {
-    use lexer::Lexer;
+    use lexer::unescape::EscapeError;
+    use lexer::{LexError, Lexer};
+    use span::{Pos, Span};
     use token::Token;
 
     #[test]
@@ -336,7 +824,7 @@ let add = fn(x, y) {
 };
 
 let result = add(five, ten);
-!-/*5;
+!-/ *5;
 5 < 10 > 5;
 
 if (5 < 10) {
@@ -469,9 +957,9 @@ if (5 < 10) {
         let mut lexer = Lexer::new(input);
 
         for expect in tests {
-            let tok = lexer.next_token();
+            let tok = lexer.next_token().unwrap();
 
-            assert_eq!(expect, tok);
+            assert_eq!(expect, tok.token);
         }
     }
 
@@ -503,9 +991,377 @@ if (5 < 10) {
         let mut lexer = Lexer::new(input);
 
         for expect in tests {
-            let tok = lexer.next_token();
+            let tok = lexer.next_token().unwrap();
+
+            assert_eq!(expect, tok.token);
+        }
+    }
+
+    #[test]
+    fn test_token_span() {
+        let input = "let x = \"抓手\";\n赋能 y";
+
+        let tests = vec![
+            (Token::Let, Span::new(Pos::new(0, 1, 1), Pos::new(3, 1, 4))),
+            (
+                Token::Ident(String::from("x")),
+                Span::new(Pos::new(4, 1, 5), Pos::new(5, 1, 6)),
+            ),
+            (
+                Token::Assign,
+                Span::new(Pos::new(6, 1, 7), Pos::new(7, 1, 8)),
+            ),
+            (
+                Token::String(String::from("抓手")),
+                Span::new(Pos::new(8, 1, 9), Pos::new(16, 1, 13)),
+            ),
+            (
+                Token::Semicolon,
+                Span::new(Pos::new(16, 1, 13), Pos::new(17, 1, 14)),
+            ),
+            (
+                Token::Let,
+                Span::new(Pos::new(18, 2, 1), Pos::new(24, 2, 3)),
+            ),
+            (
+                Token::Ident(String::from("y")),
+                Span::new(Pos::new(25, 2, 4), Pos::new(26, 2, 5)),
+            ),
+            (
+                Token::Eof,
+                Span::new(Pos::new(26, 2, 5), Pos::new(26, 2, 5)),
+            ),
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for (expect, span) in tests {
+            let tok = lexer.next_token().unwrap();
+
+            assert_eq!(expect, tok.token);
+            assert_eq!(span, tok.span);
+        }
+    }
+
+    #[test]
+    fn test_lex_errors() {
+        let tests = vec![
+            (
+                "\"abc",
+                LexError::UnterminatedString {
+                    span: Span::new(Pos::new(0, 1, 1), Pos::new(4, 1, 5)),
+                },
+            ),
+            (
+                "\"抓\\z\"",
+                LexError::InvalidEscape {
+                    kind: EscapeError::InvalidEscape,
+                    span: Span::new(Pos::new(4, 1, 3), Pos::new(6, 1, 5)),
+                },
+            ),
+            (
+                "\"\\u{D800}\"",
+                LexError::InvalidEscape {
+                    kind: EscapeError::LoneSurrogateUnicodeEscape,
+                    span: Span::new(Pos::new(1, 1, 2), Pos::new(9, 1, 10)),
+                },
+            ),
+            (
+                "@",
+                LexError::IllegalCharacter {
+                    ch: '@',
+                    span: Span::new(Pos::new(0, 1, 1), Pos::new(1, 1, 2)),
+                },
+            ),
+            (
+                "'a",
+                LexError::UnterminatedChar {
+                    span: Span::new(Pos::new(0, 1, 1), Pos::new(2, 1, 3)),
+                },
+            ),
+            (
+                "''",
+                LexError::InvalidEscape {
+                    kind: EscapeError::ZeroChars,
+                    span: Span::new(Pos::new(0, 1, 1), Pos::new(2, 1, 3)),
+                },
+            ),
+            (
+                "'ab'",
+                LexError::InvalidEscape {
+                    kind: EscapeError::MoreThanOneChar,
+                    span: Span::new(Pos::new(1, 1, 2), Pos::new(3, 1, 4)),
+                },
+            ),
+            (
+                "r#\"abc\"",
+                LexError::UnterminatedString {
+                    span: Span::new(Pos::new(0, 1, 1), Pos::new(7, 1, 8)),
+                },
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let mut lexer = Lexer::new(input);
+
+            assert_eq!(Err(expect), lexer.next_token());
+            assert_eq!(Token::Eof, lexer.next_token().unwrap().token);
+        }
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let input = "x = 1; x += 2; x -= 3; x *= 4; x /= 5; x/ 6; x %= 7; x **= 8; x &= 9; \
+                     x |= 10; x ^= 11; x <<= 12; x >>= 13; x<< 14;";
+        let tests = vec![
+            Token::Assign,
+            Token::PlusAssign,
+            Token::MinusAssign,
+            Token::AsteriskAssign,
+            Token::SlashAssign,
+            Token::Slash,
+            Token::PercentAssign,
+            Token::DoubleAsteriskAssign,
+            Token::AmpersandAssign,
+            Token::PipeAssign,
+            Token::CaretAssign,
+            Token::ShiftLeftAssign,
+            Token::ShiftRightAssign,
+            Token::ShiftLeft,
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for expect in tests {
+            lexer.next_token().unwrap();
+            assert_eq!(expect, lexer.next_token().unwrap().token);
+            lexer.next_token().unwrap();
+            lexer.next_token().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_float() {
+        let tests = vec![
+            ("3.75", Token::Float(3.75)),
+            ("0.5", Token::Float(0.5)),
+            ("1e3", Token::Float(1000.0)),
+            ("2.5E-2", Token::Float(0.025)),
+            ("6e+1", Token::Float(60.0)),
+        ];
+
+        for (input, expect) in tests {
+            assert_eq!(expect, Lexer::new(input).next_token().unwrap().token);
+        }
+
+        // A dot or an `e` that is not followed by digits is not part of the number.
+        let mut lexer = Lexer::new("1.e 2e");
+        let tests = vec![
+            Token::Int(1),
+            Token::Dot,
+            Token::Ident(String::from("e")),
+            Token::Int(2),
+            Token::Ident(String::from("e")),
+        ];
+        for expect in tests {
+            assert_eq!(expect, lexer.next_token().unwrap().token);
+        }
+    }
+
+    #[test]
+    fn test_char_and_raw_string() {
+        let input = r##"'a' '抓' '\n' '\'' '"' r"C:\n" r#"say "hi""# r ri null 留白"##;
+        let tests = vec![
+            Token::Char('a'),
+            Token::Char('抓'),
+            Token::Char('\n'),
+            Token::Char('\''),
+            Token::Char('"'),
+            Token::RawString(String::from("C:\\n"), 0),
+            Token::RawString(String::from("say \"hi\""), 1),
+            Token::Ident(String::from("r")),
+            Token::Ident(String::from("ri")),
+            Token::Null,
+            Token::Null,
+            Token::Eof,
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for expect in tests {
+            assert_eq!(expect, lexer.next_token().unwrap().token);
+        }
+    }
+
+    #[test]
+    fn test_interpolated_string() {
+        let input = r#"f"得分: {score}" f"{a}-{ {"k": f"{b}"}["k"] }{{\u{41}}}" f"" f"#;
+        let tests = vec![
+            Token::InterpStart(String::from("得分: ")),
+            Token::Ident(String::from("score")),
+            Token::InterpEnd(String::from("")),
+            Token::InterpStart(String::from("")),
+            Token::Ident(String::from("a")),
+            Token::InterpMid(String::from("-")),
+            Token::Lbrace,
+            Token::String(String::from("k")),
+            Token::Colon,
+            Token::InterpStart(String::from("")),
+            Token::Ident(String::from("b")),
+            Token::InterpEnd(String::from("")),
+            Token::Rbrace,
+            Token::Lbracket,
+            Token::String(String::from("k")),
+            Token::Rbracket,
+            Token::InterpEnd(String::from("{A}")),
+            Token::InterpString(String::from("")),
+            Token::Ident(String::from("f")),
+            Token::Eof,
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for expect in tests {
+            assert_eq!(expect, lexer.next_token().unwrap().token);
+        }
+
+        let mut lexer = Lexer::new("f\"a\\qb\"; 1");
+        assert_eq!(
+            Err(LexError::InvalidEscape {
+                kind: EscapeError::InvalidEscape,
+                span: Span::new(Pos::new(3, 1, 4), Pos::new(5, 1, 6)),
+            }),
+            lexer.next_token()
+        );
+        assert_eq!(Token::Semicolon, lexer.next_token().unwrap().token);
+        assert_eq!(Token::Int(1), lexer.next_token().unwrap().token);
+        assert_eq!(Token::Eof, lexer.next_token().unwrap().token);
+
+        let mut lexer = Lexer::new("f\"{x}ab");
+        lexer.next_token().unwrap();
+        lexer.next_token().unwrap();
+        assert_eq!(
+            Err(LexError::UnterminatedString {
+                span: Span::new(Pos::new(4, 1, 5), Pos::new(7, 1, 8)),
+            }),
+            lexer.next_token()
+        );
+    }
+
+    #[test]
+    fn test_arith_ops() {
+        let input = "% ** & | ^ ~ << >> *= <= 余量 杠杆 耦合 并联 错位 颠覆 升维 降维";
+        let tests = vec![
+            Token::Percent,
+            Token::DoubleAsterisk,
+            Token::Ampersand,
+            Token::Pipe,
+            Token::Caret,
+            Token::Tilde,
+            Token::ShiftLeft,
+            Token::ShiftRight,
+            Token::AsteriskAssign,
+            Token::LessThanEqual,
+            Token::Percent,
+            Token::DoubleAsterisk,
+            Token::Ampersand,
+            Token::Pipe,
+            Token::Caret,
+            Token::Tilde,
+            Token::ShiftLeft,
+            Token::ShiftRight,
+            Token::Eof,
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for expect in tests {
+            assert_eq!(expect, lexer.next_token().unwrap().token);
+        }
+    }
+
+    #[test]
+    fn test_logical_ops() {
+        let input = "a && b || c 协同 d 兜底 e";
+        let tests = vec![
+            Token::Ident(String::from("a")),
+            Token::And,
+            Token::Ident(String::from("b")),
+            Token::Or,
+            Token::Ident(String::from("c")),
+            Token::And,
+            Token::Ident(String::from("d")),
+            Token::Or,
+            Token::Ident(String::from("e")),
+            Token::Eof,
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for expect in tests {
+            assert_eq!(expect, lexer.next_token().unwrap().token);
+        }
+    }
 
-            assert_eq!(expect, tok);
+    #[test]
+    fn test_keywords() {
+        for keyword in Lexer::KEYWORDS {
+            match Lexer::new(keyword).next_token().unwrap().token {
+                Token::Ident(_) => panic!("{} lexed as an identifier", keyword),
+                _ => {}
+            }
         }
     }
+
+    #[test]
+    fn test_comments() {
+        let input = r#"let x = 1; // one
+/* two
+   lines */ x / 2;
+划重点 三七五
+/**/"#;
+
+        let tests = vec![
+            Token::Let,
+            Token::Ident(String::from("x")),
+            Token::Assign,
+            Token::Int(1),
+            Token::Semicolon,
+            Token::Comment(String::from("// one")),
+            Token::Comment(String::from("/* two\n   lines */")),
+            Token::Ident(String::from("x")),
+            Token::Slash,
+            Token::Int(2),
+            Token::Semicolon,
+            Token::Comment(String::from("划重点 三七五")),
+            Token::Comment(String::from("/**/")),
+            Token::Eof,
+        ];
+
+        let mut lexer = Lexer::new(input);
+
+        for expect in tests {
+            let tok = lexer.next_token().unwrap();
+
+            assert_eq!(expect, tok.token);
+        }
+
+        let mut lexer = Lexer::new("#!/usr/bin/env pua-lang\n1");
+
+        assert_eq!(
+            Token::Comment(String::from("#!/usr/bin/env pua-lang")),
+            lexer.next_token().unwrap().token
+        );
+        assert_eq!(Token::Int(1), lexer.next_token().unwrap().token);
+
+        let mut lexer = Lexer::new("1 /* 2");
+        lexer.next_token().unwrap();
+
+        assert_eq!(
+            Err(LexError::UnterminatedComment {
+                span: Span::new(Pos::new(2, 1, 3), Pos::new(6, 1, 7)),
+            }),
+            lexer.next_token()
+        );
+    }
 }
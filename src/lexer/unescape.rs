@@ -6,7 +6,7 @@ use std::ops::Range;
 use std::str::Chars;
 
 /// Errors that can occur during string unescaping.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EscapeError {
     /// Expected 1 char, but 0 were found.
     ZeroChars,
@@ -63,23 +63,63 @@ pub fn escape_str(s: &str) -> String {
     format!("{:?}", s)
 }
 
-pub fn unescape_str_or_byte_str_all(s: &str) -> String {
+/// Unescapes the contents of a string literal, stopping at the first
+/// invalid escape. The error carries the byte range of the offending
+/// escape within `s`.
+pub fn unescape_str_or_byte_str_all(s: &str) -> Result<String, (Range<usize>, EscapeError)> {
     if s.contains(&['\\', '\r'][..]) {
         let mut buf = String::with_capacity(s.len());
-        let mut error = false;
-        unescape_str_or_byte_str(&s, &mut |_, unescaped_char| {
+        let mut error = None;
+        unescape_str_or_byte_str(s, &mut |range, unescaped_char| {
             match unescaped_char {
                 Ok(c) => buf.push(c),
                 Err(e) => {
-                    error = true;
-                    buf = format!("<Lexer error: string: {:?}>", e);
+                    if error.is_none() {
+                        error = Some((range, e));
+                    }
                 }
             };
         });
-        buf
+        match error {
+            Some(error) => Err(error),
+            None => Ok(buf),
+        }
     } else {
-        s.to_string()
+        Ok(s.to_string())
+    }
+}
+
+/// Escapes the text of an `f"..."` string: like `escape_str`, but without
+/// the quotes and with braces doubled.
+pub fn escape_interp_str(s: &str) -> String {
+    let escape = |part: &str| {
+        let escaped = escape_str(part);
+        escaped[1..escaped.len() - 1].to_string()
+    };
+
+    s.split('{')
+        .map(|part| part.split('}').map(escape).collect::<Vec<_>>().join("}}"))
+        .collect::<Vec<_>>()
+        .join("{{")
+}
+
+/// Unescapes the contents of a char literal. Errors carry the byte range
+/// they refer to within `s`.
+pub fn unescape_char(s: &str) -> Result<char, (Range<usize>, EscapeError)> {
+    let mut chars = s.chars();
+    let c = match chars.next() {
+        None => return Err((0..0, EscapeError::ZeroChars)),
+        // Only strings need their double quotes escaped.
+        Some('"') => '"',
+        Some(first_char) => scan_escape(first_char, &mut chars)
+            .map_err(|err| (0..s.len() - chars.as_str().len(), err))?,
+    };
+
+    if !chars.as_str().is_empty() {
+        return Err((0..s.len(), EscapeError::MoreThanOneChar));
     }
+
+    Ok(c)
 }
 
 fn scan_escape(first_char: char, chars: &mut Chars<'_>) -> Result<char, EscapeError> {
@@ -154,12 +194,10 @@ fn scan_escape(first_char: char, chars: &mut Chars<'_>) -> Result<char, EscapeEr
                         // Incorrect syntax has higher priority for error reporting
                         // than unallowed value for a literal.
 
-                        break std::char::from_u32(value).ok_or_else(|| {
-                            if value > 0x10FFFF {
-                                EscapeError::OutOfRangeUnicodeEscape
-                            } else {
-                                EscapeError::LoneSurrogateUnicodeEscape
-                            }
+                        break std::char::from_u32(value).ok_or(if value > 0x10FFFF {
+                            EscapeError::OutOfRangeUnicodeEscape
+                        } else {
+                            EscapeError::LoneSurrogateUnicodeEscape
                         })?;
                     }
                     Some(c) => {
@@ -171,7 +209,6 @@ fn scan_escape(first_char: char, chars: &mut Chars<'_>) -> Result<char, EscapeEr
                             // Stop updating value since we're sure that it's is incorrect already.
                             continue;
                         }
-                        let digit = digit as u32;
                         value = value * 16 + digit;
                     }
                 };
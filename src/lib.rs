@@ -1,8 +1,15 @@
-#![allow(clippy::match_like_matches_macro, clippy::single_match)]
+#![allow(
+    clippy::match_like_matches_macro,
+    clippy::single_match,
+    clippy::mutable_key_type
+)]
 
 pub mod ast;
+pub mod bigint;
+pub mod diagnostics;
 pub mod evaluator;
 pub mod formatter;
 pub mod lexer;
 pub mod parser;
+pub mod span;
 pub mod token;
@@ -1,20 +1,51 @@
 use ast::*;
-use lexer::Lexer;
+use lexer::{LexError, Lexer};
+use span::{Pos, Span};
 use std::fmt;
-use token::Token;
+use token::{SpannedToken, Token};
 
 #[derive(Debug, Clone)]
 pub enum ParseError {
-    UnexpectedToken { want: Option<Token>, got: Token },
+    UnexpectedToken {
+        want: Option<Token>,
+        got: Token,
+        span: Span,
+    },
+    InvalidAssignment {
+        span: Span,
+    },
+    Lex(LexError),
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. } => *span,
+            ParseError::InvalidAssignment { span } => *span,
+            ParseError::Lex(err) => err.span(),
+        }
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &*self {
-            ParseError::UnexpectedToken { want: w, got: g } => match w {
-                Some(w) => write!(f, "Unexpected Token: expected {:?}, got {:?}", w, g),
-                None => write!(f, "Unexpected Token: no prefix rule for {:?}", g),
+        match self {
+            ParseError::UnexpectedToken {
+                want: w,
+                got: g,
+                span,
+            } => match w {
+                Some(w) => write!(
+                    f,
+                    "{}: Unexpected Token: expected {:?}, got {:?}",
+                    span, w, g
+                ),
+                None => write!(f, "{}: Unexpected Token: no prefix rule for {:?}", span, g),
             },
+            ParseError::InvalidAssignment { span } => {
+                write!(f, "{}: Invalid assignment target", span)
+            }
+            ParseError::Lex(err) => write!(f, "{}", err),
         }
     }
 }
@@ -24,8 +55,16 @@ pub type ParseErrors = Vec<ParseError>;
 pub struct Parser {
     lexer: Lexer,
     current_token: Token,
+    current_span: Span,
     next_token: Token,
+    next_span: Span,
     errors: ParseErrors,
+    comments: Vec<(String, Span)>,
+    // Number of `{` left open up to and including the current token.
+    depth: usize,
+    // Set once the current statement has reported an error, so the errors
+    // that follow from it are not reported as well.
+    recovering: bool,
 }
 
 impl Parser {
@@ -33,8 +72,13 @@ impl Parser {
         let mut parser = Parser {
             lexer,
             current_token: Token::Eof,
+            current_span: Span::default(),
             next_token: Token::Eof,
+            next_span: Span::default(),
             errors: vec![],
+            comments: vec![],
+            depth: 0,
+            recovering: false,
         };
 
         parser.bump();
@@ -45,11 +89,19 @@ impl Parser {
 
     fn token_to_precedence(tok: &Token) -> Precedence {
         match tok {
+            Token::Or => Precedence::Or,
+            Token::And => Precedence::And,
             Token::Equal | Token::NotEqual => Precedence::Equals,
             Token::LessThan | Token::LessThanEqual => Precedence::LessGreater,
             Token::GreaterThan | Token::GreaterThanEqual => Precedence::LessGreater,
+            Token::In => Precedence::LessGreater,
+            Token::Pipe => Precedence::BitOr,
+            Token::Caret => Precedence::BitXor,
+            Token::Ampersand => Precedence::BitAnd,
+            Token::ShiftLeft | Token::ShiftRight => Precedence::Shift,
             Token::Plus | Token::Minus => Precedence::Sum,
-            Token::Slash | Token::Asterisk => Precedence::Product,
+            Token::Slash | Token::Asterisk | Token::Percent => Precedence::Product,
+            Token::DoubleAsterisk => Precedence::Power,
             Token::Lbracket => Precedence::Index,
             Token::Dot => Precedence::Index,
             Token::Lparen => Precedence::Call,
@@ -62,9 +114,32 @@ impl Parser {
     }
 
     fn bump(&mut self) {
-        // FIXME: Clearly unnecessary clone
-        self.current_token = self.next_token.clone();
-        self.next_token = self.lexer.next_token();
+        let next = loop {
+            // Malformed input is reported once here and then shows up as an
+            // `Illegal` token, which the prefix rules silently refuse.
+            let next = self.lexer.next_token().unwrap_or_else(|err| {
+                let span = err.span();
+                self.errors.push(ParseError::Lex(err));
+                SpannedToken {
+                    token: Token::Illegal,
+                    span,
+                }
+            });
+            // Comments are kept aside until the enclosing block reaches a
+            // statement boundary, see `flush_comments`.
+            match next.token {
+                Token::Comment(text) => self.comments.push((text, next.span)),
+                _ => break next,
+            }
+        };
+        self.current_token = std::mem::replace(&mut self.next_token, next.token);
+        self.current_span = std::mem::replace(&mut self.next_span, next.span);
+
+        match self.current_token {
+            Token::Lbrace => self.depth += 1,
+            Token::Rbrace => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
     }
 
     fn current_token_is(&mut self, tok: Token) -> bool {
@@ -93,35 +168,113 @@ impl Parser {
         Self::token_to_precedence(&self.next_token)
     }
 
+    /// The span from `start` to the end of the current token.
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.current_span)
+    }
+
+    /// Records `err`, unless the current statement already has an error.
+    fn error(&mut self, err: ParseError) {
+        if !self.recovering {
+            self.errors.push(err);
+            self.recovering = true;
+        }
+    }
+
     fn error_next_token(&mut self, tok: Token) {
-        self.errors.push(ParseError::UnexpectedToken {
+        if self.next_token_is(&Token::Illegal) {
+            return;
+        }
+        self.error(ParseError::UnexpectedToken {
             want: Some(tok),
             got: self.next_token.clone(),
+            span: self.next_span,
         });
     }
 
-    fn error_no_prefix_parser(&mut self) {
-        self.errors.push(ParseError::UnexpectedToken {
-            want: None,
-            got: self.next_token.clone(),
+    fn error_current_token(&mut self, want: Option<Token>) {
+        if self.current_token_is(Token::Illegal) {
+            return;
+        }
+        self.error(ParseError::UnexpectedToken {
+            want,
+            got: self.current_token.clone(),
+            span: self.current_span,
         });
     }
 
+    fn error_no_prefix_parser(&mut self) {
+        self.error_current_token(None);
+    }
+
+    /// Skips the rest of a statement that failed to parse, so that it is
+    /// reported once and the statements after it still get parsed.
+    ///
+    /// `depth` is the brace depth of the block the statement belongs to.
+    /// Stops on the statement's `;`, before the next statement keyword,
+    /// before the `}` closing the block, or on it if the failure already
+    /// reached it. The caller's `bump` then moves to the next statement.
+    fn synchronize(&mut self, depth: usize) {
+        loop {
+            if self.depth < depth || self.next_token_is(&Token::Eof) {
+                return;
+            }
+            if self.depth == depth {
+                match self.current_token {
+                    Token::Semicolon | Token::Blank => return,
+                    _ => {}
+                }
+                match self.next_token {
+                    Token::Rbrace
+                    | Token::Let
+                    | Token::Return
+                    | Token::Break
+                    | Token::Continue
+                    | Token::Blank => return,
+                    _ => {}
+                }
+            }
+            self.bump();
+        }
+    }
+
+    /// Moves the pending comments that end before `pos` into `block`.
+    fn flush_comments(&mut self, pos: Pos, block: &mut BlockStmt) {
+        let count = self
+            .comments
+            .iter()
+            .take_while(|(_, span)| span.end <= pos)
+            .count();
+
+        for (text, span) in self.comments.drain(..count) {
+            block.push(Stmt::new(StmtKind::Comment(text), span));
+        }
+    }
+
+    /// Parses the whole input. Statements that fail to parse are reported
+    /// in `get_errors` and left out, so the returned program holds every
+    /// statement that did parse.
     pub fn parse(&mut self) -> Program {
         let mut program: Program = vec![];
+        let depth = self.depth;
 
         while !self.current_token_is(Token::Eof) {
+            self.flush_comments(self.current_span.start, &mut program);
+            self.recovering = false;
             match self.parse_stmt() {
                 Some(stmt) => program.push(stmt),
-                None => {}
+                None => self.synchronize(depth),
             }
             self.bump();
         }
+        self.flush_comments(self.current_span.start, &mut program);
 
         program
     }
 
     fn parse_block_stmt(&mut self) -> BlockStmt {
+        let depth = self.depth;
+
         self.bump();
 
         let mut block = vec![];
@@ -131,37 +284,43 @@ impl Parser {
                 self.error_next_token(Token::Rbrace);
                 return block;
             }
+            self.flush_comments(self.current_span.start, &mut block);
+            self.recovering = false;
             match self.parse_stmt() {
                 Some(stmt) => block.push(stmt),
-                None => {}
+                None => {
+                    self.synchronize(depth);
+                    if self.depth < depth {
+                        // The failed statement ran into our `}`.
+                        break;
+                    }
+                }
             }
             self.bump();
         }
+        self.flush_comments(self.current_span.start, &mut block);
 
         block
     }
 
     fn parse_stmt(&mut self) -> Option<Stmt> {
-        match self.current_token {
+        let start = self.current_span;
+        let kind = match self.current_token {
             Token::Let => self.parse_let_stmt(),
             Token::Return => self.parse_return_stmt(),
-            Token::Blank => Some(Stmt::Blank),
+            Token::Blank => Some(StmtKind::Blank),
             Token::Break => self.parse_break_stmt(),
             Token::Continue => self.parse_continue_stmt(),
             _ => self.parse_expr_stmt(),
-        }
+        }?;
+
+        Some(Stmt::new(kind, self.span_from(start)))
     }
 
-    fn parse_let_stmt(&mut self) -> Option<Stmt> {
-        match &self.next_token {
-            Token::Ident(_) => self.bump(),
-            _ => return None,
-        };
+    fn parse_let_stmt(&mut self) -> Option<StmtKind> {
+        self.bump();
 
-        let name = match self.parse_ident() {
-            Some(name) => name,
-            None => return None,
-        };
+        let name = self.parse_ident()?;
 
         if !self.expect_next_token(Token::Assign) {
             return None;
@@ -169,166 +328,275 @@ impl Parser {
 
         self.bump();
 
-        let expr = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
-        };
+        let expr = self.parse_expr(Precedence::Lowest)?;
 
         if self.next_token_is(&Token::Semicolon) {
             self.bump();
         }
 
-        Some(Stmt::Let(name, expr))
+        Some(StmtKind::Let(name, expr))
     }
 
-    fn parse_return_stmt(&mut self) -> Option<Stmt> {
+    fn parse_return_stmt(&mut self) -> Option<StmtKind> {
         self.bump();
 
-        let expr = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
-        };
+        let expr = self.parse_expr(Precedence::Lowest)?;
 
         if self.next_token_is(&Token::Semicolon) {
             self.bump();
         }
 
-        Some(Stmt::Return(expr))
+        Some(StmtKind::Return(expr))
     }
 
-    fn parse_break_stmt(&mut self) -> Option<Stmt> {
-        self.bump();
-
+    fn parse_break_stmt(&mut self) -> Option<StmtKind> {
         if self.next_token_is(&Token::Semicolon) {
             self.bump();
         }
 
-        Some(Stmt::Break)
+        Some(StmtKind::Break)
     }
 
-    fn parse_continue_stmt(&mut self) -> Option<Stmt> {
-        self.bump();
-
+    fn parse_continue_stmt(&mut self) -> Option<StmtKind> {
         if self.next_token_is(&Token::Semicolon) {
             self.bump();
         }
 
-        Some(Stmt::Continue)
+        Some(StmtKind::Continue)
     }
 
-    fn parse_expr_stmt(&mut self) -> Option<Stmt> {
-        match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => {
+    fn parse_expr_stmt(&mut self) -> Option<StmtKind> {
+        let expr = self.parse_expr(Precedence::Lowest)?;
+
+        let op = match self.next_token {
+            Token::Assign => None,
+            Token::PlusAssign => Some(Infix::Plus),
+            Token::MinusAssign => Some(Infix::Minus),
+            Token::AsteriskAssign => Some(Infix::Multiply),
+            Token::SlashAssign => Some(Infix::Divide),
+            Token::PercentAssign => Some(Infix::Modulo),
+            Token::DoubleAsteriskAssign => Some(Infix::Power),
+            Token::AmpersandAssign => Some(Infix::BitAnd),
+            Token::PipeAssign => Some(Infix::BitOr),
+            Token::CaretAssign => Some(Infix::BitXor),
+            Token::ShiftLeftAssign => Some(Infix::ShiftLeft),
+            Token::ShiftRightAssign => Some(Infix::ShiftRight),
+            _ => {
                 if self.next_token_is(&Token::Semicolon) {
                     self.bump();
                 }
-                Some(Stmt::Expr(expr))
+
+                return Some(StmtKind::Expr(expr));
+            }
+        };
+
+        self.parse_assign_stmt(expr, op)
+    }
+
+    fn parse_assign_stmt(&mut self, target: Expr, op: Option<Infix>) -> Option<StmtKind> {
+        match target.kind {
+            ExprKind::Ident(_) | ExprKind::Index(..) => {}
+            _ => {
+                self.error(ParseError::InvalidAssignment { span: target.span });
+                return None;
             }
-            None => None,
         }
+
+        self.bump();
+        self.bump();
+
+        let value = self.parse_expr(Precedence::Lowest)?;
+
+        if self.next_token_is(&Token::Semicolon) {
+            self.bump();
+        }
+
+        Some(StmtKind::Assign { target, op, value })
     }
 
     fn parse_expr(&mut self, precedence: Precedence) -> Option<Expr> {
+        let start = self.current_span;
+
         // prefix
-        let mut left = match self.current_token {
+        let kind = match self.current_token {
             Token::Ident(_) => self.parse_ident_expr(),
             Token::Int(_) => self.parse_int_expr(),
+            Token::BigInt(_) => self.parse_big_int_expr(),
+            Token::Float(_) => self.parse_float_expr(),
             Token::String(_) => self.parse_string_expr(),
+            Token::RawString(..) => self.parse_raw_string_expr(),
+            Token::Char(_) => self.parse_char_expr(),
+            Token::InterpString(_) | Token::InterpStart(_) => self.parse_interpolated_expr(),
             Token::Bool(_) => self.parse_bool_expr(),
+            Token::Null => Some(ExprKind::Literal(Literal::Null)),
             Token::Lbracket => self.parse_array_expr(),
             Token::Lbrace => self.parse_hash_expr(),
-            Token::Bang | Token::Minus | Token::Plus => self.parse_prefix_expr(),
+            Token::Bang | Token::Minus | Token::Plus | Token::Tilde => self.parse_prefix_expr(),
             Token::Lparen => self.parse_grouped_expr(),
             Token::If => self.parse_if_expr(),
             Token::While => self.parse_while_expr(),
+            Token::For => self.parse_for_expr(),
             Token::Func => self.parse_func_expr(),
             _ => {
                 self.error_no_prefix_parser();
                 return None;
             }
         };
+        let mut left = Expr::new(kind?, self.span_from(start));
 
         // infix
         while !self.next_token_is(&Token::Semicolon) && precedence < self.next_token_precedence() {
-            match self.next_token {
+            let kind = match self.next_token {
                 Token::Plus
                 | Token::Minus
                 | Token::Slash
                 | Token::Asterisk
+                | Token::Percent
+                | Token::DoubleAsterisk
+                | Token::Ampersand
+                | Token::Pipe
+                | Token::Caret
+                | Token::ShiftLeft
+                | Token::ShiftRight
                 | Token::Equal
                 | Token::NotEqual
                 | Token::LessThan
                 | Token::LessThanEqual
                 | Token::GreaterThan
-                | Token::GreaterThanEqual => {
+                | Token::GreaterThanEqual
+                | Token::In
+                | Token::And
+                | Token::Or => {
                     self.bump();
-                    left = self.parse_infix_expr(left.unwrap());
+                    self.parse_infix_expr(left)
                 }
                 Token::Lbracket => {
                     self.bump();
-                    left = self.parse_index_expr(left.unwrap());
+                    self.parse_index_expr(left)
                 }
                 Token::Dot => {
                     self.bump();
-                    left = self.parse_dot_access_expr(left.unwrap());
+                    self.parse_dot_access_expr(left)
                 }
                 Token::Lparen => {
                     self.bump();
-                    left = self.parse_call_expr(left.unwrap());
+                    self.parse_call_expr(left)
                 }
-                _ => return left,
-            }
+                _ => return Some(left),
+            };
+            left = Expr::new(kind?, self.span_from(start));
         }
 
-        left
+        Some(left)
     }
 
     fn parse_ident(&mut self) -> Option<Ident> {
         match self.current_token {
             Token::Ident(ref mut ident) => Some(Ident(ident.clone())),
+            _ => {
+                self.error_current_token(Some(Token::Ident(String::new())));
+                None
+            }
+        }
+    }
+
+    fn parse_ident_expr(&mut self) -> Option<ExprKind> {
+        self.parse_ident().map(ExprKind::Ident)
+    }
+
+    fn parse_int_expr(&mut self) -> Option<ExprKind> {
+        match self.current_token {
+            Token::Int(ref mut int) => Some(ExprKind::Literal(Literal::Int(*int))),
+            _ => None,
+        }
+    }
+
+    fn parse_big_int_expr(&mut self) -> Option<ExprKind> {
+        match self.current_token {
+            Token::BigInt(ref value) => Some(ExprKind::Literal(Literal::BigInt(value.clone()))),
             _ => None,
         }
     }
 
-    fn parse_ident_expr(&mut self) -> Option<Expr> {
-        self.parse_ident().map(Expr::Ident)
+    fn parse_float_expr(&mut self) -> Option<ExprKind> {
+        match self.current_token {
+            Token::Float(value) => Some(ExprKind::Literal(Literal::Float(value))),
+            _ => None,
+        }
+    }
+
+    fn parse_string_expr(&mut self) -> Option<ExprKind> {
+        match self.current_token {
+            Token::String(ref mut s) => Some(ExprKind::Literal(Literal::String(s.clone()))),
+            _ => None,
+        }
     }
 
-    fn parse_int_expr(&mut self) -> Option<Expr> {
+    fn parse_raw_string_expr(&mut self) -> Option<ExprKind> {
         match self.current_token {
-            Token::Int(ref mut int) => Some(Expr::Literal(Literal::Int(*int))),
+            Token::RawString(ref s, hashes) => {
+                Some(ExprKind::Literal(Literal::RawString(s.clone(), hashes)))
+            }
             _ => None,
         }
     }
 
-    fn parse_string_expr(&mut self) -> Option<Expr> {
+    fn parse_char_expr(&mut self) -> Option<ExprKind> {
         match self.current_token {
-            Token::String(ref mut s) => Some(Expr::Literal(Literal::String(s.clone()))),
+            Token::Char(value) => Some(ExprKind::Literal(Literal::Char(value))),
             _ => None,
         }
     }
 
-    fn parse_bool_expr(&mut self) -> Option<Expr> {
+    fn parse_interpolated_expr(&mut self) -> Option<ExprKind> {
+        let mut parts = vec![];
+
+        loop {
+            let (text, more) = match self.current_token {
+                Token::InterpString(ref text) | Token::InterpEnd(ref text) => (text.clone(), false),
+                Token::InterpStart(ref text) | Token::InterpMid(ref text) => (text.clone(), true),
+                _ => return None,
+            };
+
+            if !text.is_empty() {
+                parts.push(StringPart::Text(text));
+            }
+            if !more {
+                return Some(ExprKind::Interpolated(parts));
+            }
+
+            self.bump();
+            parts.push(StringPart::Expr(self.parse_expr(Precedence::Lowest)?));
+
+            match self.next_token {
+                Token::InterpMid(_) | Token::InterpEnd(_) => self.bump(),
+                _ => {
+                    self.error_next_token(Token::Rbrace);
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn parse_bool_expr(&mut self) -> Option<ExprKind> {
         match self.current_token {
-            Token::Bool(value) => Some(Expr::Literal(Literal::Bool(value))),
+            Token::Bool(value) => Some(ExprKind::Literal(Literal::Bool(value))),
             _ => None,
         }
     }
 
-    fn parse_array_expr(&mut self) -> Option<Expr> {
-        self.parse_expr_list(Token::Rbracket).map(|list| Expr::Literal(Literal::Array(list)))
+    fn parse_array_expr(&mut self) -> Option<ExprKind> {
+        self.parse_expr_list(Token::Rbracket)
+            .map(|list| ExprKind::Literal(Literal::Array(list)))
     }
 
-    fn parse_hash_expr(&mut self) -> Option<Expr> {
+    fn parse_hash_expr(&mut self) -> Option<ExprKind> {
         let mut pairs = Vec::new();
 
         while !self.next_token_is(&Token::Rbrace) {
             self.bump();
 
-            let key = match self.parse_expr(Precedence::Lowest) {
-                Some(expr) => expr,
-                None => return None,
-            };
+            let key = self.parse_expr(Precedence::Lowest)?;
 
             if !self.expect_next_token(Token::Colon) {
                 return None;
@@ -336,10 +604,7 @@ impl Parser {
 
             self.bump();
 
-            let value = match self.parse_expr(Precedence::Lowest) {
-                Some(expr) => expr,
-                None => return None,
-            };
+            let value = self.parse_expr(Precedence::Lowest)?;
 
             pairs.push((key, value));
 
@@ -352,7 +617,7 @@ impl Parser {
             return None;
         }
 
-        Some(Expr::Literal(Literal::Hash(pairs)))
+        Some(ExprKind::Literal(Literal::Hash(pairs)))
     }
 
     fn parse_expr_list(&mut self, end: Token) -> Option<Vec<Expr>> {
@@ -365,19 +630,13 @@ impl Parser {
 
         self.bump();
 
-        match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => list.push(expr),
-            None => return None,
-        }
+        list.push(self.parse_expr(Precedence::Lowest)?);
 
         while self.next_token_is(&Token::Comma) {
             self.bump();
             self.bump();
 
-            match self.parse_expr(Precedence::Lowest) {
-                Some(expr) => list.push(expr),
-                None => return None,
-            }
+            list.push(self.parse_expr(Precedence::Lowest)?);
         }
 
         if !self.expect_next_token(end) {
@@ -387,71 +646,124 @@ impl Parser {
         Some(list)
     }
 
-    fn parse_prefix_expr(&mut self) -> Option<Expr> {
+    fn parse_prefix_expr(&mut self) -> Option<ExprKind> {
         let prefix = match self.current_token {
             Token::Bang => Prefix::Not,
             Token::Minus => Prefix::Minus,
             Token::Plus => Prefix::Plus,
+            Token::Tilde => Prefix::BitNot,
             _ => return None,
         };
 
         self.bump();
 
-        self.parse_expr(Precedence::Prefix).map(|expr| Expr::Prefix(prefix, Box::new(expr)))
+        self.parse_expr(Precedence::Prefix)
+            .map(|expr| ExprKind::Prefix(prefix, Box::new(expr)))
     }
 
-    fn parse_infix_expr(&mut self, left: Expr) -> Option<Expr> {
+    fn parse_infix_expr(&mut self, left: Expr) -> Option<ExprKind> {
         let infix = match self.current_token {
             Token::Plus => Infix::Plus,
             Token::Minus => Infix::Minus,
             Token::Slash => Infix::Divide,
             Token::Asterisk => Infix::Multiply,
+            Token::Percent => Infix::Modulo,
+            Token::DoubleAsterisk => Infix::Power,
+            Token::Ampersand => Infix::BitAnd,
+            Token::Pipe => Infix::BitOr,
+            Token::Caret => Infix::BitXor,
+            Token::ShiftLeft => Infix::ShiftLeft,
+            Token::ShiftRight => Infix::ShiftRight,
             Token::Equal => Infix::Equal,
             Token::NotEqual => Infix::NotEqual,
             Token::LessThan => Infix::LessThan,
             Token::LessThanEqual => Infix::LessThanEqual,
             Token::GreaterThan => Infix::GreaterThan,
             Token::GreaterThanEqual => Infix::GreaterThanEqual,
+            Token::In => Infix::In,
+            Token::And => Infix::And,
+            Token::Or => Infix::Or,
             _ => return None,
         };
 
-        let precedence = self.current_token_precedence();
+        // `**` groups to the right, so its right side may hold another `**`.
+        let precedence = match infix {
+            Infix::Power => Precedence::Prefix,
+            _ => self.current_token_precedence(),
+        };
 
         self.bump();
 
-        self.parse_expr(precedence).map(|expr| Expr::Infix(infix, Box::new(left), Box::new(expr)))
+        self.parse_expr(precedence)
+            .map(|expr| ExprKind::Infix(infix, Box::new(left), Box::new(expr)))
     }
 
-    fn parse_index_expr(&mut self, left: Expr) -> Option<Expr> {
+    fn parse_index_expr(&mut self, left: Expr) -> Option<ExprKind> {
+        self.bump();
+
+        let start = self.parse_slice_bound()?;
+
+        if !self.current_token_is(Token::Colon) {
+            let index = match start {
+                Some(index) if self.current_token_is(Token::Rbracket) => index,
+                Some(_) => {
+                    self.error_current_token(Some(Token::Rbracket));
+                    return None;
+                }
+                None => {
+                    self.error_no_prefix_parser();
+                    return None;
+                }
+            };
+            return Some(ExprKind::Index(Box::new(left), index));
+        }
+
         self.bump();
+        let end = self.parse_slice_bound()?;
 
-        let index = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
+        let step = if self.current_token_is(Token::Colon) {
+            self.bump();
+            self.parse_slice_bound()?
+        } else {
+            None
         };
 
-        if !self.expect_next_token(Token::Rbracket) {
+        if !self.current_token_is(Token::Rbracket) {
+            self.error_current_token(Some(Token::Rbracket));
             return None;
         }
 
-        Some(Expr::Index(Box::new(left), Box::new(index)))
+        Some(ExprKind::Slice {
+            left: Box::new(left),
+            start,
+            end,
+            step,
+        })
+    }
+
+    /// Parses one bound of a slice and moves onto the `:` or `]` after it.
+    /// A bound that is left out gives `None`.
+    fn parse_slice_bound(&mut self) -> Option<Option<Box<Expr>>> {
+        match self.current_token {
+            Token::Colon | Token::Rbracket => Some(None),
+            _ => {
+                let bound = self.parse_expr(Precedence::Lowest)?;
+                self.bump();
+                Some(Some(Box::new(bound)))
+            }
+        }
     }
 
-    fn parse_dot_access_expr(&mut self, left: Expr) -> Option<Expr> {
+    fn parse_dot_access_expr(&mut self, left: Expr) -> Option<ExprKind> {
         self.bump();
 
-        match self.parse_ident() {
-            Some(name) => match name {
-                Ident(str) => {
-                    Some(Expr::Index(Box::new(left), Box::new(Expr::Literal(Literal::String(str)))))
-                },
-                _ => return None
-            },
-            None => return None,
-        }
+        let Ident(name) = self.parse_ident()?;
+        let key = Expr::new(ExprKind::Literal(Literal::String(name)), self.current_span);
+
+        Some(ExprKind::Index(Box::new(left), Box::new(key)))
     }
 
-    fn parse_grouped_expr(&mut self) -> Option<Expr> {
+    fn parse_grouped_expr(&mut self) -> Option<ExprKind> {
         self.bump();
 
         let expr = self.parse_expr(Precedence::Lowest);
@@ -459,57 +771,65 @@ impl Parser {
         if !self.expect_next_token(Token::Rparen) {
             None
         } else {
-            expr
+            expr.map(|expr| expr.kind)
         }
     }
 
-    fn parse_if_expr(&mut self) -> Option<Expr> {
+    fn parse_if_expr(&mut self) -> Option<ExprKind> {
         if !self.expect_next_token(Token::Lparen) {
             return None;
         }
 
         self.bump();
 
-        let cond = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
-        };
+        let cond = self.parse_expr(Precedence::Lowest)?;
 
         if !self.expect_next_token(Token::Rparen) || !self.expect_next_token(Token::Lbrace) {
             return None;
         }
 
-        let consequence = self.parse_block_stmt();
+        let mut consequence = self.parse_block_stmt();
         let mut alternative = None;
 
         if self.next_token_is(&Token::Else) {
+            // Comments between `}` and `else` stay with the branch they
+            // follow rather than moving into the `else` block.
+            self.flush_comments(self.next_span.start, &mut consequence);
             self.bump();
 
-            if !self.expect_next_token(Token::Lbrace) {
-                return None;
-            }
+            if self.next_token_is(&Token::If) {
+                // `else if` is an `else` block holding just the next `if`.
+                self.bump();
+
+                let start = self.current_span;
+                let kind = self.parse_if_expr()?;
+                let span = self.span_from(start);
+
+                alternative = Some(vec![Stmt::new(StmtKind::Expr(Expr::new(kind, span)), span)]);
+            } else {
+                if !self.expect_next_token(Token::Lbrace) {
+                    return None;
+                }
 
-            alternative = Some(self.parse_block_stmt());
+                alternative = Some(self.parse_block_stmt());
+            }
         }
 
-        Some(Expr::If {
+        Some(ExprKind::If {
             cond: Box::new(cond),
             consequence,
             alternative,
         })
     }
 
-    fn parse_while_expr(&mut self) -> Option<Expr> {
+    fn parse_while_expr(&mut self) -> Option<ExprKind> {
         if !self.expect_next_token(Token::Lparen) {
             return None;
         }
 
         self.bump();
 
-        let cond = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
-        };
+        let cond = self.parse_expr(Precedence::Lowest)?;
 
         if !self.expect_next_token(Token::Rparen) || !self.expect_next_token(Token::Lbrace) {
             return None;
@@ -517,27 +837,59 @@ impl Parser {
 
         let consequence = self.parse_block_stmt();
 
-        Some(Expr::While {
+        Some(ExprKind::While {
             cond: Box::new(cond),
             consequence,
         })
     }
 
-    fn parse_func_expr(&mut self) -> Option<Expr> {
+    fn parse_for_expr(&mut self) -> Option<ExprKind> {
         if !self.expect_next_token(Token::Lparen) {
             return None;
         }
 
-        let params = match self.parse_func_params() {
-            Some(params) => params,
-            None => return None,
-        };
+        self.bump();
+
+        let mut vars = vec![self.parse_ident()?];
+
+        if self.next_token_is(&Token::Comma) {
+            self.bump();
+            self.bump();
+
+            vars.push(self.parse_ident()?);
+        }
+
+        if !self.expect_next_token(Token::In) {
+            return None;
+        }
+
+        self.bump();
+
+        let iterable = self.parse_expr(Precedence::Lowest)?;
+
+        if !self.expect_next_token(Token::Rparen) || !self.expect_next_token(Token::Lbrace) {
+            return None;
+        }
+
+        Some(ExprKind::For {
+            vars,
+            iterable: Box::new(iterable),
+            body: self.parse_block_stmt(),
+        })
+    }
+
+    fn parse_func_expr(&mut self) -> Option<ExprKind> {
+        if !self.expect_next_token(Token::Lparen) {
+            return None;
+        }
+
+        let params = self.parse_func_params()?;
 
         if !self.expect_next_token(Token::Lbrace) {
             return None;
         }
 
-        Some(Expr::Func {
+        Some(ExprKind::Func {
             params,
             body: self.parse_block_stmt(),
         })
@@ -553,19 +905,13 @@ impl Parser {
 
         self.bump();
 
-        match self.parse_ident() {
-            Some(ident) => params.push(ident),
-            None => return None,
-        };
+        params.push(self.parse_ident()?);
 
         while self.next_token_is(&Token::Comma) {
             self.bump();
             self.bump();
 
-            match self.parse_ident() {
-                Some(ident) => params.push(ident),
-                None => return None,
-            };
+            params.push(self.parse_ident()?);
         }
 
         if !self.expect_next_token(Token::Rparen) {
@@ -575,13 +921,10 @@ impl Parser {
         Some(params)
     }
 
-    fn parse_call_expr(&mut self, func: Expr) -> Option<Expr> {
-        let args = match self.parse_expr_list(Token::Rparen) {
-            Some(args) => args,
-            None => return None,
-        };
+    fn parse_call_expr(&mut self, func: Expr) -> Option<ExprKind> {
+        let args = self.parse_expr_list(Token::Rparen)?;
 
-        Some(Expr::Call {
+        Some(ExprKind::Call {
             func: Box::new(func),
             args,
         })
@@ -592,7 +935,9 @@ impl Parser {
 mod tests {
     use ast::*;
     use lexer::Lexer;
-    use parser::Parser;
+    use parser::{ParseError, Parser};
+    use span::{Pos, Span};
+    use token::Token;
 
     fn check_parse_errors(parser: &mut Parser) {
         let errors = parser.get_errors();
@@ -637,22 +982,30 @@ if (x) {
         check_parse_errors(&mut parser);
         assert_eq!(
             vec![
-                Stmt::Expr(Expr::Literal(Literal::Int(1000))),
-                Stmt::Blank,
-                Stmt::Expr(Expr::Literal(Literal::Int(1000))),
-                Stmt::Blank,
-                Stmt::Blank,
-                Stmt::Expr(Expr::Literal(Literal::Int(1000))),
-                Stmt::Blank,
-                Stmt::Expr(Expr::If {
-                    cond: Box::new(Expr::Ident(Ident(String::from("x")))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(Literal::Int(
+                    1000
+                ))))),
+                Stmt::from(StmtKind::Blank),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(Literal::Int(
+                    1000
+                ))))),
+                Stmt::from(StmtKind::Blank),
+                Stmt::from(StmtKind::Blank),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(Literal::Int(
+                    1000
+                ))))),
+                Stmt::from(StmtKind::Blank),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::If {
+                    cond: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("x"))))),
                     consequence: vec![
-                        Stmt::Blank,
-                        Stmt::Expr(Expr::Ident(Ident(String::from("x")))),
-                        Stmt::Blank,
+                        Stmt::from(StmtKind::Blank),
+                        Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(Ident(
+                            String::from("x")
+                        ))))),
+                        Stmt::from(StmtKind::Blank),
                     ],
                     alternative: None,
-                }),
+                }))),
             ],
             program,
         );
@@ -672,17 +1025,108 @@ let foobar = 838383;
         check_parse_errors(&mut parser);
         assert_eq!(
             vec![
-                Stmt::Let(Ident(String::from("x")), Expr::Literal(Literal::Int(5))),
-                Stmt::Let(Ident(String::from("y")), Expr::Literal(Literal::Int(10))),
-                Stmt::Let(
+                Stmt::from(StmtKind::Let(
+                    Ident(String::from("x")),
+                    Expr::from(ExprKind::Literal(Literal::Int(5)))
+                )),
+                Stmt::from(StmtKind::Let(
+                    Ident(String::from("y")),
+                    Expr::from(ExprKind::Literal(Literal::Int(10)))
+                )),
+                Stmt::from(StmtKind::Let(
                     Ident(String::from("foobar")),
-                    Expr::Literal(Literal::Int(838383)),
-                ),
+                    Expr::from(ExprKind::Literal(Literal::Int(838383))),
+                )),
             ],
             program,
         );
     }
 
+    #[test]
+    fn test_assign_stmt() {
+        let tests = vec![
+            (
+                "x = 5;",
+                StmtKind::Assign {
+                    target: Expr::from(ExprKind::Ident(Ident(String::from("x")))),
+                    op: None,
+                    value: Expr::from(ExprKind::Literal(Literal::Int(5))),
+                },
+            ),
+            (
+                "a[0] += 1",
+                StmtKind::Assign {
+                    target: Expr::from(ExprKind::Index(
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(0)))),
+                    )),
+                    op: Some(Infix::Plus),
+                    value: Expr::from(ExprKind::Literal(Literal::Int(1))),
+                },
+            ),
+            (
+                "h.key /= 2 * 3;",
+                StmtKind::Assign {
+                    target: Expr::from(ExprKind::Index(
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("h"))))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::String(
+                            String::from("key"),
+                        )))),
+                    )),
+                    op: Some(Infix::Divide),
+                    value: Expr::from(ExprKind::Infix(
+                        Infix::Multiply,
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                    )),
+                },
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(vec![Stmt::from(expect)], program);
+        }
+
+        let ops = vec![
+            ("%=", Infix::Modulo),
+            ("**=", Infix::Power),
+            ("&=", Infix::BitAnd),
+            ("|=", Infix::BitOr),
+            ("^=", Infix::BitXor),
+            ("<<=", Infix::ShiftLeft),
+            (">>=", Infix::ShiftRight),
+        ];
+
+        for (op, infix) in ops {
+            let input = format!("x {} 2;", op);
+            let mut parser = Parser::new(Lexer::new(&input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(
+                vec![Stmt::from(StmtKind::Assign {
+                    target: Expr::from(ExprKind::Ident(Ident(String::from("x")))),
+                    op: Some(infix),
+                    value: Expr::from(ExprKind::Literal(Literal::Int(2))),
+                })],
+                program
+            );
+        }
+
+        let mut parser = Parser::new(Lexer::new("f() = 1; x = 2;"));
+        let program = parser.parse();
+
+        match &parser.get_errors()[..] {
+            [ParseError::InvalidAssignment { span }] => assert_eq!(Pos::new(0, 1, 1), span.start),
+            errors => panic!("unexpected errors: {:?}", errors),
+        }
+        assert_eq!(1, program.len());
+    }
+
     #[test]
     fn test_return_stmt() {
         let input = r#"
@@ -697,9 +1141,15 @@ return 993322;
         check_parse_errors(&mut parser);
         assert_eq!(
             vec![
-                Stmt::Return(Expr::Literal(Literal::Int(5))),
-                Stmt::Return(Expr::Literal(Literal::Int(10))),
-                Stmt::Return(Expr::Literal(Literal::Int(993322))),
+                Stmt::from(StmtKind::Return(Expr::from(ExprKind::Literal(
+                    Literal::Int(5)
+                )))),
+                Stmt::from(StmtKind::Return(Expr::from(ExprKind::Literal(
+                    Literal::Int(10)
+                )))),
+                Stmt::from(StmtKind::Return(Expr::from(ExprKind::Literal(
+                    Literal::Int(993322)
+                )))),
             ],
             program,
         );
@@ -714,7 +1164,9 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Ident(Ident(String::from("foobar"))))],
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(
+                Ident(String::from("foobar"))
+            ))))],
             program,
         );
     }
@@ -727,123 +1179,231 @@ return 993322;
         let program = parser.parse();
 
         check_parse_errors(&mut parser);
-        assert_eq!(vec![Stmt::Expr(Expr::Literal(Literal::Int(5)))], program,);
+        assert_eq!(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                Literal::Int(5)
+            ))))],
+            program,
+        );
     }
 
     #[test]
-    fn test_string_literal_expr() {
-        let input = "\"hello world\";";
+    fn test_big_int_literal_expr() {
+        let input = "99999999999999999999;";
 
         let mut parser = Parser::new(Lexer::new(input));
         let program = parser.parse();
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Literal(Literal::String(String::from(
-                "hello world",
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                Literal::BigInt("99999999999999999999".parse().unwrap())
             ))))],
             program,
         );
     }
 
     #[test]
-    fn test_boolean_literal_expr() {
-        let tests = vec![
-            ("true;", Stmt::Expr(Expr::Literal(Literal::Bool(true)))),
-            ("false;", Stmt::Expr(Expr::Literal(Literal::Bool(false)))),
-        ];
+    fn test_float_literal_expr() {
+        let input = "3.75;";
 
-        for (input, expect) in tests {
-            let mut parser = Parser::new(Lexer::new(input));
-            let program = parser.parse();
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
 
-            check_parse_errors(&mut parser);
-            assert_eq!(vec![expect], program);
-        }
+        check_parse_errors(&mut parser);
+        assert_eq!(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                Literal::Float(3.75)
+            ))))],
+            program,
+        );
     }
 
     #[test]
-    fn test_array_literal_expr() {
-        let input = "[1, 2 * 2, 3 + 3]";
+    fn test_string_literal_expr() {
+        let input = "\"hello world\";";
 
         let mut parser = Parser::new(Lexer::new(input));
         let program = parser.parse();
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Literal(Literal::Array(vec![
-                Expr::Literal(Literal::Int(1)),
-                Expr::Infix(
-                    Infix::Multiply,
-                    Box::new(Expr::Literal(Literal::Int(2))),
-                    Box::new(Expr::Literal(Literal::Int(2))),
-                ),
-                Expr::Infix(
-                    Infix::Plus,
-                    Box::new(Expr::Literal(Literal::Int(3))),
-                    Box::new(Expr::Literal(Literal::Int(3))),
-                ),
-            ])))],
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                Literal::String(String::from("hello world",))
+            ))))],
             program,
         );
     }
 
     #[test]
-    fn test_hash_literal_expr() {
+    fn test_char_raw_string_and_null_literal_expr() {
         let tests = vec![
-            ("{}", Stmt::Expr(Expr::Literal(Literal::Hash(vec![])))),
-            (
+            ("'抓';", Literal::Char('抓')),
+            ("r#\"a\\b\"#;", Literal::RawString(String::from("a\\b"), 1)),
+            ("null;", Literal::Null),
+            ("留白;", Literal::Null),
+        ];
+
+        for (input, expect) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(
+                vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    expect
+                ))))],
+                program,
+            );
+        }
+    }
+
+    #[test]
+    fn test_interpolated_expr() {
+        let input = "f\"得分: {score + 1}分\"; f\"\";";
+
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
+
+        check_parse_errors(&mut parser);
+        assert_eq!(
+            vec![
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Interpolated(vec![
+                    StringPart::Text(String::from("得分: ")),
+                    StringPart::Expr(Expr::from(ExprKind::Infix(
+                        Infix::Plus,
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("score"))))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                    ))),
+                    StringPart::Text(String::from("分")),
+                ])))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Interpolated(vec![])))),
+            ],
+            program,
+        );
+    }
+
+    #[test]
+    fn test_boolean_literal_expr() {
+        let tests = vec![
+            (
+                "true;",
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Bool(true),
+                )))),
+            ),
+            (
+                "false;",
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Bool(false),
+                )))),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(vec![expect], program);
+        }
+    }
+
+    #[test]
+    fn test_array_literal_expr() {
+        let input = "[1, 2 * 2, 3 + 3]";
+
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
+
+        check_parse_errors(&mut parser);
+        assert_eq!(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                Literal::Array(vec![
+                    Expr::from(ExprKind::Literal(Literal::Int(1))),
+                    Expr::from(ExprKind::Infix(
+                        Infix::Multiply,
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                    )),
+                    Expr::from(ExprKind::Infix(
+                        Infix::Plus,
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                    )),
+                ])
+            ))))],
+            program,
+        );
+    }
+
+    #[test]
+    fn test_hash_literal_expr() {
+        let tests = vec![
+            (
+                "{}",
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Hash(vec![]),
+                )))),
+            ),
+            (
                 "{\"one\": 1, \"two\": 2, \"three\": 3}",
-                Stmt::Expr(Expr::Literal(Literal::Hash(vec![
-                    (
-                        Expr::Literal(Literal::String(String::from("one"))),
-                        Expr::Literal(Literal::Int(1)),
-                    ),
-                    (
-                        Expr::Literal(Literal::String(String::from("two"))),
-                        Expr::Literal(Literal::Int(2)),
-                    ),
-                    (
-                        Expr::Literal(Literal::String(String::from("three"))),
-                        Expr::Literal(Literal::Int(3)),
-                    ),
-                ]))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Hash(vec![
+                        (
+                            Expr::from(ExprKind::Literal(Literal::String(String::from("one")))),
+                            Expr::from(ExprKind::Literal(Literal::Int(1))),
+                        ),
+                        (
+                            Expr::from(ExprKind::Literal(Literal::String(String::from("two")))),
+                            Expr::from(ExprKind::Literal(Literal::Int(2))),
+                        ),
+                        (
+                            Expr::from(ExprKind::Literal(Literal::String(String::from("three")))),
+                            Expr::from(ExprKind::Literal(Literal::Int(3))),
+                        ),
+                    ]),
+                )))),
             ),
             (
                 "{\"one\": 0 + 1, \"two\": 10 - 8, \"three\": 15 / 5}",
-                Stmt::Expr(Expr::Literal(Literal::Hash(vec![
-                    (
-                        Expr::Literal(Literal::String(String::from("one"))),
-                        Expr::Infix(
-                            Infix::Plus,
-                            Box::new(Expr::Literal(Literal::Int(0))),
-                            Box::new(Expr::Literal(Literal::Int(1))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Hash(vec![
+                        (
+                            Expr::from(ExprKind::Literal(Literal::String(String::from("one")))),
+                            Expr::from(ExprKind::Infix(
+                                Infix::Plus,
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(0)))),
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                            )),
                         ),
-                    ),
-                    (
-                        Expr::Literal(Literal::String(String::from("two"))),
-                        Expr::Infix(
-                            Infix::Minus,
-                            Box::new(Expr::Literal(Literal::Int(10))),
-                            Box::new(Expr::Literal(Literal::Int(8))),
+                        (
+                            Expr::from(ExprKind::Literal(Literal::String(String::from("two")))),
+                            Expr::from(ExprKind::Infix(
+                                Infix::Minus,
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(10)))),
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(8)))),
+                            )),
                         ),
-                    ),
-                    (
-                        Expr::Literal(Literal::String(String::from("three"))),
-                        Expr::Infix(
-                            Infix::Divide,
-                            Box::new(Expr::Literal(Literal::Int(15))),
-                            Box::new(Expr::Literal(Literal::Int(5))),
+                        (
+                            Expr::from(ExprKind::Literal(Literal::String(String::from("three")))),
+                            Expr::from(ExprKind::Infix(
+                                Infix::Divide,
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(15)))),
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                            )),
                         ),
-                    ),
-                ]))),
+                    ]),
+                )))),
             ),
             (
                 "{key: \"value\"}",
-                Stmt::Expr(Expr::Literal(Literal::Hash(vec![(
-                    Expr::Ident(Ident(String::from("key"))),
-                    Expr::Literal(Literal::String(String::from("value"))),
-                )]))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Hash(vec![(
+                        Expr::from(ExprKind::Ident(Ident(String::from("key")))),
+                        Expr::from(ExprKind::Literal(Literal::String(String::from("value")))),
+                    )]),
+                )))),
             ),
         ];
 
@@ -865,18 +1425,56 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Index(
-                Box::new(Expr::Ident(Ident(String::from("myArray")))),
-                Box::new(Expr::Infix(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Index(
+                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("myArray"))))),
+                Box::new(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Literal(Literal::Int(1))),
-                    Box::new(Expr::Literal(Literal::Int(1))),
-                )),
-            ))],
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                ))),
+            ))))],
             program
         );
     }
 
+    #[test]
+    fn test_slice_expr() {
+        let ident = |name: &str| Box::new(Expr::from(ExprKind::Ident(Ident(String::from(name)))));
+        let int = |value| Box::new(Expr::from(ExprKind::Literal(Literal::Int(value))));
+        let slice = |start, end, step| {
+            Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Slice {
+                left: ident("a"),
+                start,
+                end,
+                step,
+            })))
+        };
+
+        let tests = vec![
+            ("a[1:2]", slice(Some(int(1)), Some(int(2)), None)),
+            ("a[:2]", slice(None, Some(int(2)), None)),
+            ("a[1:]", slice(Some(int(1)), None, None)),
+            ("a[:]", slice(None, None, None)),
+            ("a[::2]", slice(None, None, Some(int(2)))),
+            ("a[i:j:]", slice(Some(ident("i")), Some(ident("j")), None)),
+        ];
+
+        for (input, expect) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(vec![expect], program);
+        }
+
+        for input in &["a[]", "a[1 2]", "a[1:2 3]", "a[1:2:3:4]"] {
+            let mut parser = Parser::new(Lexer::new(input));
+            parser.parse();
+
+            assert_eq!(1, parser.get_errors().len(), "{}", input);
+        }
+    }
+
     #[test]
     fn test_dot_access_expr() {
         let input = "myHash.key";
@@ -886,10 +1484,12 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Index(
-                Box::new(Expr::Ident(Ident(String::from("myHash")))),
-                Box::new(Expr::Literal(Literal::String(String::from("key")))),
-            ))],
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Index(
+                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("myHash"))))),
+                Box::new(Expr::from(ExprKind::Literal(Literal::String(
+                    String::from("key")
+                )))),
+            ))))],
             program
         );
     }
@@ -899,24 +1499,24 @@ return 993322;
         let tests = vec![
             (
                 "!5;",
-                Stmt::Expr(Expr::Prefix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Prefix(
                     Prefix::Not,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "-15;",
-                Stmt::Expr(Expr::Prefix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Prefix(
                     Prefix::Minus,
-                    Box::new(Expr::Literal(Literal::Int(15))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(15)))),
+                )))),
             ),
             (
                 "+15;",
-                Stmt::Expr(Expr::Prefix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Prefix(
                     Prefix::Plus,
-                    Box::new(Expr::Literal(Literal::Int(15))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(15)))),
+                )))),
             ),
         ];
 
@@ -934,83 +1534,83 @@ return 993322;
         let tests = vec![
             (
                 "5 + 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 - 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Minus,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 * 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Multiply,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 / 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Divide,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 > 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::GreaterThan,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 < 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::LessThan,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 == 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Equal,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 != 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::NotEqual,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 >= 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::GreaterThanEqual,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
             (
                 "5 <= 5;",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::LessThanEqual,
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                    Box::new(Expr::Literal(Literal::Int(5))),
-                )),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                )))),
             ),
         ];
 
@@ -1032,15 +1632,17 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::If {
-                cond: Box::new(Expr::Infix(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::If {
+                cond: Box::new(Expr::from(ExprKind::Infix(
                     Infix::LessThan,
-                    Box::new(Expr::Ident(Ident(String::from("x")))),
-                    Box::new(Expr::Ident(Ident(String::from("y")))),
-                )),
-                consequence: vec![Stmt::Expr(Expr::Ident(Ident(String::from("x"))))],
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("x"))))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("y"))))),
+                ))),
+                consequence: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(
+                    Ident(String::from("x"))
+                ))))],
                 alternative: None,
-            })],
+            })))],
             program,
         );
     }
@@ -1054,19 +1656,91 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::If {
-                cond: Box::new(Expr::Infix(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::If {
+                cond: Box::new(Expr::from(ExprKind::Infix(
                     Infix::LessThan,
-                    Box::new(Expr::Ident(Ident(String::from("x")))),
-                    Box::new(Expr::Ident(Ident(String::from("y")))),
-                )),
-                consequence: vec![Stmt::Expr(Expr::Ident(Ident(String::from("x"))))],
-                alternative: Some(vec![Stmt::Expr(Expr::Ident(Ident(String::from("y"))))]),
-            })],
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("x"))))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("y"))))),
+                ))),
+                consequence: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(
+                    Ident(String::from("x"))
+                ))))],
+                alternative: Some(vec![Stmt::from(StmtKind::Expr(Expr::from(
+                    ExprKind::Ident(Ident(String::from("y")))
+                )))]),
+            })))],
             program,
         );
     }
 
+    #[test]
+    fn test_else_if_expr() {
+        let input = "if (a) { 1 } else if (b) { 2 } else { 3 }";
+
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
+
+        check_parse_errors(&mut parser);
+        assert_eq!(
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::If {
+                cond: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                consequence: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Int(1)
+                ))))],
+                alternative: Some(vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::If {
+                    cond: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                    consequence: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                        Literal::Int(2)
+                    ))))],
+                    alternative: Some(vec![Stmt::from(StmtKind::Expr(Expr::from(
+                        ExprKind::Literal(Literal::Int(3))
+                    )))]),
+                })))]),
+            })))],
+            program,
+        );
+
+        match &program[0].kind {
+            StmtKind::Expr(Expr {
+                kind:
+                    ExprKind::If {
+                        alternative: Some(alternative),
+                        ..
+                    },
+                ..
+            }) => assert_eq!(
+                Span::new(Pos::new(18, 1, 19), Pos::new(41, 1, 42)),
+                alternative[0].span
+            ),
+            stmt => panic!("expected if expression, got {:?}", stmt),
+        }
+    }
+
+    #[test]
+    fn test_for_expr() {
+        let tests = vec![
+            ("for (x in xs) { x }", vec!["x"]),
+            ("拉通 (i, x 沉淀 xs) { x }", vec!["i", "x"]),
+        ];
+
+        for (input, vars) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(
+                vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::For {
+                    vars: vars.into_iter().map(|v| Ident(String::from(v))).collect(),
+                    iterable: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("xs"))))),
+                    body: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(
+                        Ident(String::from("x"))
+                    ))))],
+                })))],
+                program,
+            );
+        }
+    }
+
     #[test]
     fn test_func_expr() {
         let input = "fn(x, y) { x + y; }";
@@ -1076,14 +1750,14 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Func {
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Func {
                 params: vec![Ident(String::from("x")), Ident(String::from("y"))],
-                body: vec![Stmt::Expr(Expr::Infix(
+                body: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Ident(Ident(String::from("x")))),
-                    Box::new(Expr::Ident(Ident(String::from("y")))),
-                ))],
-            })],
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("x"))))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("y"))))),
+                ))))],
+            })))],
             program,
         );
     }
@@ -1109,10 +1783,10 @@ return 993322;
 
             check_parse_errors(&mut parser);
             assert_eq!(
-                vec![Stmt::Expr(Expr::Func {
+                vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Func {
                     params: expect,
                     body: vec![],
-                })],
+                })))],
                 program,
             );
         }
@@ -1127,446 +1801,553 @@ return 993322;
 
         check_parse_errors(&mut parser);
         assert_eq!(
-            vec![Stmt::Expr(Expr::Call {
-                func: Box::new(Expr::Ident(Ident(String::from("add")))),
+            vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Call {
+                func: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("add"))))),
                 args: vec![
-                    Expr::Literal(Literal::Int(1)),
-                    Expr::Infix(
+                    Expr::from(ExprKind::Literal(Literal::Int(1))),
+                    Expr::from(ExprKind::Infix(
                         Infix::Multiply,
-                        Box::new(Expr::Literal(Literal::Int(2))),
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                    ),
-                    Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                    )),
+                    Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                    ),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    )),
                 ],
-            })],
+            })))],
             program,
         );
     }
 
+    #[test]
+    fn test_arith_ops_precedence() {
+        let ident = |name: &str| Box::new(Expr::from(ExprKind::Ident(Ident(String::from(name)))));
+        let infix = |infix, left, right| Box::new(Expr::from(ExprKind::Infix(infix, left, right)));
+        let prefix = |prefix, right| Box::new(Expr::from(ExprKind::Prefix(prefix, right)));
+
+        let tests = vec![
+            (
+                "a | b ^ c & d << e + f % g",
+                infix(
+                    Infix::BitOr,
+                    ident("a"),
+                    infix(
+                        Infix::BitXor,
+                        ident("b"),
+                        infix(
+                            Infix::BitAnd,
+                            ident("c"),
+                            infix(
+                                Infix::ShiftLeft,
+                                ident("d"),
+                                infix(
+                                    Infix::Plus,
+                                    ident("e"),
+                                    infix(Infix::Modulo, ident("f"), ident("g")),
+                                ),
+                            ),
+                        ),
+                    ),
+                ),
+            ),
+            (
+                "a & b == c",
+                infix(
+                    Infix::Equal,
+                    infix(Infix::BitAnd, ident("a"), ident("b")),
+                    ident("c"),
+                ),
+            ),
+            (
+                "a >> b >> c",
+                infix(
+                    Infix::ShiftRight,
+                    infix(Infix::ShiftRight, ident("a"), ident("b")),
+                    ident("c"),
+                ),
+            ),
+            (
+                "a ** b ** c",
+                infix(
+                    Infix::Power,
+                    ident("a"),
+                    infix(Infix::Power, ident("b"), ident("c")),
+                ),
+            ),
+            (
+                "-a ** b * c",
+                infix(
+                    Infix::Multiply,
+                    prefix(Prefix::Minus, infix(Infix::Power, ident("a"), ident("b"))),
+                    ident("c"),
+                ),
+            ),
+            (
+                "~a ** -b",
+                prefix(
+                    Prefix::BitNot,
+                    infix(Infix::Power, ident("a"), prefix(Prefix::Minus, ident("b"))),
+                ),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            let program = parser.parse();
+
+            check_parse_errors(&mut parser);
+            assert_eq!(vec![Stmt::from(StmtKind::Expr(*expect))], program);
+        }
+    }
+
     #[test]
     fn test_operator_precedence_parsing() {
         let tests = vec![
             (
                 "-a * b",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Multiply,
-                    Box::new(Expr::Prefix(
+                    Box::new(Expr::from(ExprKind::Prefix(
                         Prefix::Minus,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("b")))),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                )))),
             ),
             (
                 "!-a",
-                Stmt::Expr(Expr::Prefix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Prefix(
                     Prefix::Not,
-                    Box::new(Expr::Prefix(
+                    Box::new(Expr::from(ExprKind::Prefix(
                         Prefix::Minus,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                    ))),
+                )))),
+            ),
+            (
+                "a || b && c == d",
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
+                    Infix::Or,
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                    Box::new(Expr::from(ExprKind::Infix(
+                        Infix::And,
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                        Box::new(Expr::from(ExprKind::Infix(
+                            Infix::Equal,
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("d"))))),
+                        ))),
+                    ))),
+                )))),
             ),
             (
                 "a + b + c",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                        Box::new(Expr::Ident(Ident(String::from("b")))),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("c")))),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                )))),
             ),
             (
                 "a + b - c",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Minus,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                        Box::new(Expr::Ident(Ident(String::from("b")))),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("c")))),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                )))),
             ),
             (
                 "a * b * c",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Multiply,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Multiply,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                        Box::new(Expr::Ident(Ident(String::from("b")))),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("c")))),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                )))),
             ),
             (
                 "a * b / c",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Divide,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Multiply,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                        Box::new(Expr::Ident(Ident(String::from("b")))),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("c")))),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                )))),
             ),
             (
                 "a + b / c",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Ident(Ident(String::from("a")))),
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Divide,
-                        Box::new(Expr::Ident(Ident(String::from("b")))),
-                        Box::new(Expr::Ident(Ident(String::from("c")))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                    ))),
+                )))),
             ),
             (
                 "a + b * c + d / e - f",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Minus,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Plus,
-                            Box::new(Expr::Ident(Ident(String::from("a")))),
-                            Box::new(Expr::Infix(
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                            Box::new(Expr::from(ExprKind::Infix(
                                 Infix::Multiply,
-                                Box::new(Expr::Ident(Ident(String::from("b")))),
-                                Box::new(Expr::Ident(Ident(String::from("c")))),
-                            )),
-                        )),
-                        Box::new(Expr::Infix(
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                            ))),
+                        ))),
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Divide,
-                            Box::new(Expr::Ident(Ident(String::from("d")))),
-                            Box::new(Expr::Ident(Ident(String::from("e")))),
-                        )),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("f")))),
-                )),
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("d"))))),
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("e"))))),
+                        ))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("f"))))),
+                )))),
             ),
             (
                 "5 > 4 == 3 < 4",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Equal,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::GreaterThan,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                    Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::LessThan,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                )))),
             ),
             (
                 "5 < 4 != 3 > 4",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::NotEqual,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::LessThan,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                    Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::GreaterThan,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                )))),
             ),
             (
                 "5 >= 4 == 3 <= 4",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Equal,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::GreaterThanEqual,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                    Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::LessThanEqual,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                )))),
             ),
             (
                 "5 <= 4 != 3 >= 4",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::NotEqual,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::LessThanEqual,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                    Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::GreaterThanEqual,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Literal(Literal::Int(4))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                    ))),
+                )))),
             ),
             (
                 "3 + 4 * 5 == 3 * 1 + 4 * 5",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Equal,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Multiply,
-                            Box::new(Expr::Literal(Literal::Int(4))),
-                            Box::new(Expr::Literal(Literal::Int(5))),
-                        )),
-                    )),
-                    Box::new(Expr::Infix(
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        ))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Multiply,
-                            Box::new(Expr::Literal(Literal::Int(3))),
-                            Box::new(Expr::Literal(Literal::Int(1))),
-                        )),
-                        Box::new(Expr::Infix(
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                        ))),
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Multiply,
-                            Box::new(Expr::Literal(Literal::Int(4))),
-                            Box::new(Expr::Literal(Literal::Int(5))),
-                        )),
-                    )),
-                )),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        ))),
+                    ))),
+                )))),
+            ),
+            (
+                "true",
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Bool(true),
+                )))),
+            ),
+            (
+                "false",
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Literal(
+                    Literal::Bool(false),
+                )))),
             ),
-            ("true", Stmt::Expr(Expr::Literal(Literal::Bool(true)))),
-            ("false", Stmt::Expr(Expr::Literal(Literal::Bool(false)))),
             (
                 "3 > 5 == false",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Equal,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::GreaterThan,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                    )),
-                    Box::new(Expr::Literal(Literal::Bool(false))),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Bool(false)))),
+                )))),
             ),
             (
                 "3 < 5 == true",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Equal,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::LessThan,
-                        Box::new(Expr::Literal(Literal::Int(3))),
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                    )),
-                    Box::new(Expr::Literal(Literal::Bool(true))),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Bool(true)))),
+                )))),
             ),
             (
                 "1 + (2 + 3) + 4",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Literal(Literal::Int(1))),
-                        Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Plus,
-                            Box::new(Expr::Literal(Literal::Int(2))),
-                            Box::new(Expr::Literal(Literal::Int(3))),
-                        )),
-                    )),
-                    Box::new(Expr::Literal(Literal::Int(4))),
-                )),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        ))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                )))),
             ),
             (
                 "(5 + 5) * 2",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Multiply,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                    )),
-                    Box::new(Expr::Literal(Literal::Int(2))),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                )))),
             ),
             (
                 "2 / (5 + 5)",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Divide,
-                    Box::new(Expr::Literal(Literal::Int(2))),
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    ))),
+                )))),
             ),
             (
                 "-(5 + 5)",
-                Stmt::Expr(Expr::Prefix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Prefix(
                     Prefix::Minus,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                        Box::new(Expr::Literal(Literal::Int(5))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                    ))),
+                )))),
             ),
             (
                 "!(true == true)",
-                Stmt::Expr(Expr::Prefix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Prefix(
                     Prefix::Not,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Equal,
-                        Box::new(Expr::Literal(Literal::Bool(true))),
-                        Box::new(Expr::Literal(Literal::Bool(true))),
-                    )),
-                )),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Bool(true)))),
+                        Box::new(Expr::from(ExprKind::Literal(Literal::Bool(true)))),
+                    ))),
+                )))),
             ),
             (
                 "a + add(b * c) + d",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Plus,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                        Box::new(Expr::Call {
-                            func: Box::new(Expr::Ident(Ident(String::from("add")))),
-                            args: vec![Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Call {
+                            func: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("add"))))),
+                            args: vec![Expr::from(ExprKind::Infix(
                                 Infix::Multiply,
-                                Box::new(Expr::Ident(Ident(String::from("b")))),
-                                Box::new(Expr::Ident(Ident(String::from("c")))),
-                            )],
-                        }),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("d")))),
-                )),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                            ))],
+                        })),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("d"))))),
+                )))),
             ),
             (
                 "add(a, b, 1, 2 * 3, 4 + 5, add(6, 7 * 8))",
-                Stmt::Expr(Expr::Call {
-                    func: Box::new(Expr::Ident(Ident(String::from("add")))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Call {
+                    func: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("add"))))),
                     args: vec![
-                        Expr::Ident(Ident(String::from("a"))),
-                        Expr::Ident(Ident(String::from("b"))),
-                        Expr::Literal(Literal::Int(1)),
-                        Expr::Infix(
+                        Expr::from(ExprKind::Ident(Ident(String::from("a")))),
+                        Expr::from(ExprKind::Ident(Ident(String::from("b")))),
+                        Expr::from(ExprKind::Literal(Literal::Int(1))),
+                        Expr::from(ExprKind::Infix(
                             Infix::Multiply,
-                            Box::new(Expr::Literal(Literal::Int(2))),
-                            Box::new(Expr::Literal(Literal::Int(3))),
-                        ),
-                        Expr::Infix(
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(3)))),
+                        )),
+                        Expr::from(ExprKind::Infix(
                             Infix::Plus,
-                            Box::new(Expr::Literal(Literal::Int(4))),
-                            Box::new(Expr::Literal(Literal::Int(5))),
-                        ),
-                        Expr::Call {
-                            func: Box::new(Expr::Ident(Ident(String::from("add")))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(4)))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(5)))),
+                        )),
+                        Expr::from(ExprKind::Call {
+                            func: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("add"))))),
                             args: vec![
-                                Expr::Literal(Literal::Int(6)),
-                                Expr::Infix(
+                                Expr::from(ExprKind::Literal(Literal::Int(6))),
+                                Expr::from(ExprKind::Infix(
                                     Infix::Multiply,
-                                    Box::new(Expr::Literal(Literal::Int(7))),
-                                    Box::new(Expr::Literal(Literal::Int(8))),
-                                ),
+                                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(7)))),
+                                    Box::new(Expr::from(ExprKind::Literal(Literal::Int(8)))),
+                                )),
                             ],
-                        },
+                        }),
                     ],
-                }),
+                }))),
             ),
             (
                 "add(a + b + c * d / f + g)",
-                Stmt::Expr(Expr::Call {
-                    func: Box::new(Expr::Ident(Ident(String::from("add")))),
-                    args: vec![Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Call {
+                    func: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("add"))))),
+                    args: vec![Expr::from(ExprKind::Infix(
                         Infix::Plus,
-                        Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Infix(
                             Infix::Plus,
-                            Box::new(Expr::Infix(
+                            Box::new(Expr::from(ExprKind::Infix(
                                 Infix::Plus,
-                                Box::new(Expr::Ident(Ident(String::from("a")))),
-                                Box::new(Expr::Ident(Ident(String::from("b")))),
-                            )),
-                            Box::new(Expr::Infix(
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                            ))),
+                            Box::new(Expr::from(ExprKind::Infix(
                                 Infix::Divide,
-                                Box::new(Expr::Infix(
+                                Box::new(Expr::from(ExprKind::Infix(
                                     Infix::Multiply,
-                                    Box::new(Expr::Ident(Ident(String::from("c")))),
-                                    Box::new(Expr::Ident(Ident(String::from("d")))),
-                                )),
-                                Box::new(Expr::Ident(Ident(String::from("f")))),
-                            )),
-                        )),
-                        Box::new(Expr::Ident(Ident(String::from("g")))),
-                    )],
-                }),
+                                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("d"))))),
+                                ))),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("f"))))),
+                            ))),
+                        ))),
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("g"))))),
+                    ))],
+                }))),
             ),
             (
                 "a * [1, 2, 3, 4][b * c] * d",
-                Stmt::Expr(Expr::Infix(
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Infix(
                     Infix::Multiply,
-                    Box::new(Expr::Infix(
+                    Box::new(Expr::from(ExprKind::Infix(
                         Infix::Multiply,
-                        Box::new(Expr::Ident(Ident(String::from("a")))),
-                        Box::new(Expr::Index(
-                            Box::new(Expr::Literal(Literal::Array(vec![
-                                Expr::Literal(Literal::Int(1)),
-                                Expr::Literal(Literal::Int(2)),
-                                Expr::Literal(Literal::Int(3)),
-                                Expr::Literal(Literal::Int(4)),
-                            ]))),
-                            Box::new(Expr::Infix(
+                        Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                        Box::new(Expr::from(ExprKind::Index(
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Array(vec![
+                                Expr::from(ExprKind::Literal(Literal::Int(1))),
+                                Expr::from(ExprKind::Literal(Literal::Int(2))),
+                                Expr::from(ExprKind::Literal(Literal::Int(3))),
+                                Expr::from(ExprKind::Literal(Literal::Int(4))),
+                            ])))),
+                            Box::new(Expr::from(ExprKind::Infix(
                                 Infix::Multiply,
-                                Box::new(Expr::Ident(Ident(String::from("b")))),
-                                Box::new(Expr::Ident(Ident(String::from("c")))),
-                            )),
-                        )),
-                    )),
-                    Box::new(Expr::Ident(Ident(String::from("d")))),
-                )),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("c"))))),
+                            ))),
+                        ))),
+                    ))),
+                    Box::new(Expr::from(ExprKind::Ident(Ident(String::from("d"))))),
+                )))),
             ),
             (
                 "add(a * b[2], b[1], 2 * [1, 2][1])",
-                Stmt::Expr(Expr::Call {
-                    func: Box::new(Expr::Ident(Ident(String::from("add")))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Call {
+                    func: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("add"))))),
                     args: vec![
-                        Expr::Infix(
+                        Expr::from(ExprKind::Infix(
                             Infix::Multiply,
-                            Box::new(Expr::Ident(Ident(String::from("a")))),
-                            Box::new(Expr::Index(
-                                Box::new(Expr::Ident(Ident(String::from("b")))),
-                                Box::new(Expr::Literal(Literal::Int(2))),
-                            )),
-                        ),
-                        Expr::Index(
-                            Box::new(Expr::Ident(Ident(String::from("b")))),
-                            Box::new(Expr::Literal(Literal::Int(1))),
-                        ),
-                        Expr::Infix(
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("a"))))),
+                            Box::new(Expr::from(ExprKind::Index(
+                                Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                            ))),
+                        )),
+                        Expr::from(ExprKind::Index(
+                            Box::new(Expr::from(ExprKind::Ident(Ident(String::from("b"))))),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                        )),
+                        Expr::from(ExprKind::Infix(
                             Infix::Multiply,
-                            Box::new(Expr::Literal(Literal::Int(2))),
-                            Box::new(Expr::Index(
-                                Box::new(Expr::Literal(Literal::Array(vec![
-                                    Expr::Literal(Literal::Int(1)),
-                                    Expr::Literal(Literal::Int(2)),
-                                ]))),
-                                Box::new(Expr::Literal(Literal::Int(1))),
-                            )),
-                        ),
+                            Box::new(Expr::from(ExprKind::Literal(Literal::Int(2)))),
+                            Box::new(Expr::from(ExprKind::Index(
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Array(vec![
+                                    Expr::from(ExprKind::Literal(Literal::Int(1))),
+                                    Expr::from(ExprKind::Literal(Literal::Int(2))),
+                                ])))),
+                                Box::new(Expr::from(ExprKind::Literal(Literal::Int(1)))),
+                            ))),
+                        )),
                     ],
-                }),
+                }))),
             ),
         ];
 
@@ -1578,4 +2359,160 @@ return 993322;
             assert_eq!(vec![expect], program);
         }
     }
+
+    #[test]
+    fn test_spans() {
+        let input = "let x = 1;\nfoo(x + 2)";
+
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
+
+        check_parse_errors(&mut parser);
+        assert_eq!(
+            Span::new(Pos::new(0, 1, 1), Pos::new(10, 1, 11)),
+            program[0].span
+        );
+        assert_eq!(
+            Span::new(Pos::new(11, 2, 1), Pos::new(21, 2, 11)),
+            program[1].span
+        );
+
+        match &program[1].kind {
+            StmtKind::Expr(Expr {
+                kind: ExprKind::Call { args, .. },
+                ..
+            }) => assert_eq!(
+                Span::new(Pos::new(15, 2, 5), Pos::new(20, 2, 10)),
+                args[0].span
+            ),
+            stmt => panic!("expected call expression, got {:?}", stmt),
+        }
+    }
+
+    #[test]
+    fn test_error_span() {
+        let mut parser = Parser::new(Lexer::new("let x = (1 + 2;"));
+        parser.parse();
+
+        match &parser.get_errors()[0] {
+            ParseError::UnexpectedToken {
+                want: Some(Token::Rparen),
+                got: Token::Semicolon,
+                span,
+            } => assert_eq!(Pos::new(14, 1, 15), span.start),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let input = r#"let x = (1 + 2;
+let = 5;
+fn(a) {
+  let y = a +;
+  y
+};
+foo(1, 2 let z = 3;
+if (z) { 1 + }
+z"#;
+
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
+
+        let lines = parser
+            .get_errors()
+            .iter()
+            .map(|err| err.span().start.line)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 2, 4, 7, 8], lines);
+        assert_eq!(
+            vec![
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Func {
+                    params: vec![Ident(String::from("a"))],
+                    body: vec![Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(
+                        Ident(String::from("y"))
+                    ))))],
+                }))),
+                Stmt::from(StmtKind::Let(
+                    Ident(String::from("z")),
+                    Expr::from(ExprKind::Literal(Literal::Int(3))),
+                )),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::If {
+                    cond: Box::new(Expr::from(ExprKind::Ident(Ident(String::from("z"))))),
+                    consequence: vec![],
+                    alternative: None,
+                }))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(Ident(
+                    String::from("z")
+                ))))),
+            ],
+            program,
+        );
+
+        for input in &["let y = (", "fn() { let y = (", "f(1, [2"] {
+            let mut parser = Parser::new(Lexer::new(input));
+            parser.parse();
+
+            assert_eq!(1, parser.get_errors().len(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_lex_errors() {
+        let tests = vec![
+            ("let s = \"abc", "1:9: Unterminated string literal"),
+            (
+                "let s = \"a\\qb\";",
+                "1:11: Invalid escape in string literal: InvalidEscape",
+            ),
+            ("foo(1 @ 2)", "1:7: Illegal character: '@'"),
+            ("let c = 'a;", "1:9: Unterminated char literal"),
+        ];
+
+        for (input, expect) in tests {
+            let mut parser = Parser::new(Lexer::new(input));
+            parser.parse();
+
+            match &parser.get_errors()[0] {
+                ParseError::Lex(err) => assert_eq!(expect, format!("{}", err)),
+                err => panic!("expected lex error, got {:?}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = r#"// head
+let x = 1; // one
+fn() {
+  /* body */
+  x
+};"#;
+
+        let mut parser = Parser::new(Lexer::new(input));
+        let program = parser.parse();
+
+        check_parse_errors(&mut parser);
+        assert_eq!(
+            vec![
+                Stmt::from(StmtKind::Comment(String::from("// head"))),
+                Stmt::from(StmtKind::Let(
+                    Ident(String::from("x")),
+                    Expr::from(ExprKind::Literal(Literal::Int(1))),
+                )),
+                Stmt::from(StmtKind::Comment(String::from("// one"))),
+                Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Func {
+                    params: vec![],
+                    body: vec![
+                        Stmt::from(StmtKind::Comment(String::from("/* body */"))),
+                        Stmt::from(StmtKind::Expr(Expr::from(ExprKind::Ident(Ident(
+                            String::from("x")
+                        ))))),
+                    ],
+                }))),
+            ],
+            program,
+        );
+        assert_eq!(2, program[2].span.start.line);
+    }
 }
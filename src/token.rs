@@ -1,3 +1,8 @@
+use bigint::BigInt;
+use lexer::unescape::{escape_interp_str, escape_str};
+use span::Span;
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Illegal,
@@ -7,14 +12,43 @@ pub enum Token {
     // Identifiers + literals
     Ident(String),
     Int(i64),
+    /// An integer literal too large for `Int`.
+    BigInt(BigInt),
+    Float(f64),
     String(String),
+    /// `r"..."`, with the number of `#`s around the quotes.
+    RawString(String, usize),
+    Char(char),
+    /// An `f"..."` string without holes.
+    InterpString(String),
+    /// The text of an `f"..."` string up to its first hole, `f"...{`.
+    InterpStart(String),
+    /// The text between two holes, `}...{`.
+    InterpMid(String),
+    /// The text after the last hole, `}..."`.
+    InterpEnd(String),
     Bool(bool),
+    Null,
+    Comment(String),
 
     // Statements
     Assign,
+    PlusAssign,
+    MinusAssign,
+    AsteriskAssign,
+    SlashAssign,
+    PercentAssign,
+    DoubleAsteriskAssign,
+    AmpersandAssign,
+    PipeAssign,
+    CaretAssign,
+    ShiftLeftAssign,
+    ShiftRightAssign,
     If,
     Else,
     While,
+    For,
+    In,
     Break,
     Continue,
 
@@ -24,6 +58,14 @@ pub enum Token {
     Bang,
     Asterisk,
     Slash,
+    Percent,
+    DoubleAsterisk,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
 
     Equal,
     NotEqual,
@@ -31,6 +73,8 @@ pub enum Token {
     LessThanEqual,
     GreaterThan,
     GreaterThanEqual,
+    And,
+    Or,
 
     // Delimiters
     Comma,
@@ -49,3 +93,90 @@ pub enum Token {
     Let,
     Return,
 }
+
+/// Prints the Monkey spelling of the token.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Illegal => write!(f, "<illegal>"),
+            Token::Blank => write!(f, "<blank line>"),
+            Token::Eof => write!(f, "<eof>"),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Int(value) => write!(f, "{}", value),
+            Token::BigInt(ref value) => write!(f, "{}", value),
+            Token::Float(value) => write!(f, "{:?}", value),
+            Token::String(value) => write!(f, "{}", escape_str(value)),
+            Token::RawString(value, hashes) => {
+                write!(f, "r{0}\"{1}\"{0}", "#".repeat(*hashes), value)
+            }
+            Token::Char(value) => write!(f, "{:?}", value),
+            Token::InterpString(value) => write!(f, "f\"{}\"", escape_interp_str(value)),
+            Token::InterpStart(value) => write!(f, "f\"{}{{", escape_interp_str(value)),
+            Token::InterpMid(value) => write!(f, "}}{}{{", escape_interp_str(value)),
+            Token::InterpEnd(value) => write!(f, "}}{}\"", escape_interp_str(value)),
+            Token::Bool(value) => write!(f, "{}", value),
+            Token::Null => write!(f, "null"),
+            Token::Comment(text) => write!(f, "{}", text),
+            Token::Assign => write!(f, "="),
+            Token::PlusAssign => write!(f, "+="),
+            Token::MinusAssign => write!(f, "-="),
+            Token::AsteriskAssign => write!(f, "*="),
+            Token::SlashAssign => write!(f, "/="),
+            Token::PercentAssign => write!(f, "%="),
+            Token::DoubleAsteriskAssign => write!(f, "**="),
+            Token::AmpersandAssign => write!(f, "&="),
+            Token::PipeAssign => write!(f, "|="),
+            Token::CaretAssign => write!(f, "^="),
+            Token::ShiftLeftAssign => write!(f, "<<="),
+            Token::ShiftRightAssign => write!(f, ">>="),
+            Token::If => write!(f, "if"),
+            Token::Else => write!(f, "else"),
+            Token::While => write!(f, "while"),
+            Token::For => write!(f, "for"),
+            Token::In => write!(f, "in"),
+            Token::Break => write!(f, "break"),
+            Token::Continue => write!(f, "continue"),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Bang => write!(f, "!"),
+            Token::Asterisk => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::Percent => write!(f, "%"),
+            Token::DoubleAsterisk => write!(f, "**"),
+            Token::Ampersand => write!(f, "&"),
+            Token::Pipe => write!(f, "|"),
+            Token::Caret => write!(f, "^"),
+            Token::Tilde => write!(f, "~"),
+            Token::ShiftLeft => write!(f, "<<"),
+            Token::ShiftRight => write!(f, ">>"),
+            Token::Equal => write!(f, "=="),
+            Token::NotEqual => write!(f, "!="),
+            Token::LessThan => write!(f, "<"),
+            Token::LessThanEqual => write!(f, "<="),
+            Token::GreaterThan => write!(f, ">"),
+            Token::GreaterThanEqual => write!(f, ">="),
+            Token::And => write!(f, "&&"),
+            Token::Or => write!(f, "||"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
+            Token::Semicolon => write!(f, ";"),
+            Token::Lparen => write!(f, "("),
+            Token::Rparen => write!(f, ")"),
+            Token::Lbrace => write!(f, "{{"),
+            Token::Rbrace => write!(f, "}}"),
+            Token::Lbracket => write!(f, "["),
+            Token::Rbracket => write!(f, "]"),
+            Token::Dot => write!(f, "."),
+            Token::Func => write!(f, "fn"),
+            Token::Let => write!(f, "let"),
+            Token::Return => write!(f, "return"),
+        }
+    }
+}
+
+/// A token together with the source span it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
@@ -1,6 +1,7 @@
 extern crate pua_lang;
 
 use pua_lang::ast::Program;
+use pua_lang::diagnostics::Diagnostic;
 use pua_lang::evaluator::builtins::new_builtins;
 use pua_lang::evaluator::env::Env;
 use pua_lang::evaluator::object::Object;
@@ -14,6 +15,9 @@ use std::mem;
 use std::os::raw::{c_char, c_void};
 use std::rc::Rc;
 
+/// Name shown for the editor buffer in diagnostics.
+const FILE_NAME: &str = "<playground>";
+
 fn main() {}
 
 extern "C" {
@@ -37,8 +41,8 @@ fn parse(input: &str) -> Result<Program, String> {
 
     if errors.len() > 0 {
         let msg = errors
-            .into_iter()
-            .map(|e| format!("{}\n", e))
+            .iter()
+            .map(|e| Diagnostic::from(e).render(FILE_NAME, input))
             .collect::<String>();
 
         return Err(msg);
@@ -78,7 +82,7 @@ pub fn eval(input_ptr: *mut c_char) -> *mut c_char {
 
     env.set(
         String::from("输出"),
-        &Object::Builtin(-1, |args| {
+        &Object::Builtin(-1, |_, args| {
             for arg in args {
                 internal_print(&format!("{}", arg));
             }
@@ -88,7 +92,11 @@ pub fn eval(input_ptr: *mut c_char) -> *mut c_char {
 
     let mut evaluator = Evaluator::new(Rc::new(RefCell::new(env)));
     let evaluated = evaluator.eval(&program).unwrap_or(Object::Null);
-    let output = format!("{}", evaluated);
+    let output = match evaluated {
+        Object::Error(msg) => Diagnostic::runtime(&msg, evaluator.error_span().unwrap_or_default())
+            .render(FILE_NAME, &input),
+        _ => format!("{}", evaluated),
+    };
 
     string_to_ptr(output)
 }